@@ -1,36 +1,393 @@
-use std::io;
-
-fn part1() -> Result<usize, String> {
-    let lines = include_str!("data.txt").lines();
-    let counter = lines
-        .filter_map(|v| v.parse::<i32>().ok())
-        .collect::<Vec<i32>>()
-        .windows(2)
-        .filter(|pair| pair[0] < pair[1])
-        .count();
-    Ok(counter)
-}
-
-fn part2() -> Result<usize, String> {
-    let lines = include_str!("data.txt").lines();
-
-    let counter = lines
-        .filter_map(|v| v.parse::<i32>().ok())
-        .collect::<Vec<i32>>()
-        .windows(3)
-        .map(|w| w.iter().sum())
-        .collect::<Vec<i32>>()
-        .windows(2)
-        .filter(|pair| pair[0] < pair[1])
-        .count();
-
-    Ok(counter)
-}
-
-fn main() -> io::Result<()> {
-    let result1 = part1().unwrap();
-    println!("result#1: {}", result1); // 1121
-    let result2 = part2().unwrap();
-    println!("result#2: {}", result2); // 1065
-    Ok(())
+use std::{process::ExitCode, str::FromStr, time::Duration};
+
+use stats::StatsOptions;
+use utils::runner::{Command, Runner};
+
+mod stats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Increase,
+    Decrease,
+    /// Absolute difference between neighbouring sums is larger than the value
+    ChangeAbove(i64),
+}
+
+impl Comparison {
+    fn matches(&self, previous: i64, current: i64) -> bool {
+        match self {
+            Comparison::Increase => current > previous,
+            Comparison::Decrease => current < previous,
+            Comparison::ChangeAbove(threshold) => (current - previous).abs() > *threshold,
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "increase" => Ok(Comparison::Increase),
+            "decrease" => Ok(Comparison::Decrease),
+            _ => match input.strip_prefix("change:") {
+                Some(threshold) => threshold
+                    .parse()
+                    .map(Comparison::ChangeAbove)
+                    .map_err(|_| format!("Invalid threshold: {}", threshold)),
+                None => Err(format!("Invalid comparison: {}", input)),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ParseMode {
+    /// Any malformed line is an error
+    #[default]
+    Strict,
+    /// Malformed lines are skipped and counted
+    Lenient,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Depths {
+    values: Vec<i32>,
+    skipped: usize,
+}
+
+fn parse_depths(data: &str, mode: ParseMode) -> Result<Depths, String> {
+    let mut depths = Depths {
+        values: Vec::new(),
+        skipped: 0,
+    };
+
+    for (idx, line) in data.lines().enumerate() {
+        match line.trim().parse::<i32>() {
+            Ok(value) => depths.values.push(value),
+            Err(_) if mode == ParseMode::Lenient => depths.skipped += 1,
+            Err(_) => {
+                return Err(format!(
+                    "Invalid depth reading on line {}: {:?}",
+                    idx + 1,
+                    line
+                ))
+            }
+        }
+    }
+
+    Ok(depths)
+}
+
+/// Sums of every `window` consecutive readings. The sum is updated in place
+/// while sliding (drop the oldest reading, add the newest one), so this is a
+/// single pass without allocations.
+fn window_sums(depths: &[i32], window: usize) -> impl Iterator<Item = i64> + '_ {
+    let valid = window > 0 && depths.len() >= window;
+    let mut sum: i64 = if valid {
+        depths[..window].iter().map(|&depth| depth as i64).sum()
+    } else {
+        0
+    };
+
+    let first = valid.then_some(sum);
+    let rest = (window..depths.len())
+        .filter(move |_| valid)
+        .map(move |idx| {
+            sum += depths[idx] as i64 - depths[idx - window] as i64;
+            sum
+        });
+
+    first.into_iter().chain(rest)
+}
+
+/// Compares the sums of neighbouring sliding windows of `window` readings and
+/// counts how many of them match `comparison`. A window of 1 compares the
+/// readings themselves.
+fn count_increases(depths: &[i32], window: usize, comparison: Comparison) -> usize {
+    let mut sums = window_sums(depths, window);
+    let mut previous = match sums.next() {
+        Some(sum) => sum,
+        None => return 0,
+    };
+    let mut counter = 0;
+
+    for sum in sums {
+        if comparison.matches(previous, sum) {
+            counter += 1;
+        }
+
+        previous = sum;
+    }
+
+    counter
+}
+
+fn part1(data: &str) -> Result<usize, String> {
+    let depths = parse_depths(data, ParseMode::Strict)?;
+    Ok(count_increases(&depths.values, 1, Comparison::Increase))
+}
+
+fn part2(data: &str) -> Result<usize, String> {
+    let depths = parse_depths(data, ParseMode::Strict)?;
+    Ok(count_increases(&depths.values, 3, Comparison::Increase))
+}
+
+#[derive(Debug)]
+struct Options {
+    comparison: Comparison,
+    mode: ParseMode,
+    input: Option<String>,
+    json: bool,
+    stats: StatsOptions,
+}
+
+/// Arguments shared by the `count` and `stats` commands, the others being
+/// rejected by the command they don't apply to:
+///
+/// `[--window <size>] [--input <file>] [--lenient]`
+/// `[--compare increase|decrease|change:<threshold>]` (count)
+/// `[--top <n>] [--bucket <width>] [--k <deviations>] [--format table|json]` (stats)
+fn parse_options(name: &str, args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        comparison: Comparison::Increase,
+        mode: ParseMode::default(),
+        input: None,
+        json: false,
+        stats: StatsOptions::default(),
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        let invalid = |value: &str| format!("Invalid value for {}: {}", arg, value);
+
+        match arg.as_str() {
+            "--compare" if name != "count" => return Err(format!("{} doesn't take {}", name, arg)),
+            "--top" | "--bucket" | "--k" | "--format" if name != "stats" => {
+                return Err(format!("{} doesn't take {}", name, arg))
+            }
+            "--lenient" => options.mode = ParseMode::Lenient,
+            "--window" => {
+                let value = value()?;
+                options.stats.window = match value.parse() {
+                    Ok(window) if window > 0 => window,
+                    _ => return Err(invalid(value)),
+                };
+            }
+            "--compare" => options.comparison = Comparison::from_str(value()?)?,
+            "--input" => options.input = Some(Runner::read_input(value()?)?),
+            "--top" => {
+                let value = value()?;
+                options.stats.top = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--bucket" => {
+                let value = value()?;
+                options.stats.bucket_width = match value.parse() {
+                    Ok(width) if width > 0 => width,
+                    _ => return Err(invalid(value)),
+                };
+            }
+            "--k" => {
+                let value = value()?;
+                options.stats.k = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--format" => match value()?.as_str() {
+                "table" => options.json = false,
+                "json" => options.json = true,
+                other => return Err(invalid(other)),
+            },
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn load_depths(data: &str, options: &Options) -> Result<Vec<i32>, String> {
+    let input = options.input.as_deref().unwrap_or(data);
+    let depths = parse_depths(input, options.mode)?;
+
+    if depths.skipped > 0 {
+        eprintln!("Skipped {} malformed line(s)", depths.skipped);
+    }
+    if depths.values.len() < options.stats.window {
+        return Err(format!(
+            "Window of {} readings is longer than the input, which has {}",
+            options.stats.window,
+            depths.values.len()
+        ));
+    }
+
+    Ok(depths.values)
+}
+
+fn day_command(data: &str, name: &str, args: &[String]) -> Result<String, String> {
+    let options = parse_options(name, args)?;
+
+    match name {
+        "count" => {
+            let depths = load_depths(data, &options)?;
+            let count = count_increases(&depths, options.stats.window, options.comparison);
+            Ok(format!("{count}\n"))
+        }
+        "stats" => {
+            let depths = load_depths(data, &options)?;
+            let stats = stats::analyse(&depths, &options.stats);
+            Ok(if options.json {
+                stats.to_json()
+            } else {
+                stats.to_table()
+            })
+        }
+        _ => Err(format!("Unknown command: {}", name)),
+    }
+}
+
+fn main() -> ExitCode {
+    let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match runner.command() {
+        Command::Day { name, args } => runner.print_day_output(day_command(data, &name, &args)),
+        _ => {
+            // 1121
+            runner.run_fallible("result#1", move |_| part1(data));
+            // 1065
+            runner.run_fallible("result#2", move |_| part2(data));
+        }
+    }
+
+    runner.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn part1_example() {
+        let result = part1(include_str!("data_small.txt")).unwrap();
+        assert_eq!(result, 5);
+    }
+
+    #[test]
+    fn part2_example() {
+        let result = part2(include_str!("data_small.txt")).unwrap();
+        assert_eq!(result, 4);
+    }
+
+    #[test]
+    fn count_increases_comparisons() {
+        let depths = parse_depths(include_str!("data_small.txt"), ParseMode::Strict)
+            .unwrap()
+            .values;
+        // 171 154 155 170 167 170 176 199
+        assert_eq!(count_increases(&depths, 1, Comparison::Decrease), 2);
+        assert_eq!(count_increases(&depths, 1, Comparison::ChangeAbove(9)), 3);
+        // window sums: 480 479 492 507 513 545
+        assert_eq!(count_increases(&depths, 3, Comparison::Decrease), 1);
+        assert_eq!(count_increases(&depths, 8, Comparison::Increase), 0);
+        assert_eq!(count_increases(&depths, 0, Comparison::Increase), 0);
+    }
+
+    #[test]
+    fn depth_stats() {
+        let depths = parse_depths(include_str!("data_small.txt"), ParseMode::Strict)
+            .unwrap()
+            .values;
+        // 171 154 155 170 167 170 176 199
+        let stats = stats::analyse(
+            &depths,
+            &StatsOptions {
+                top: 2,
+                k: 1.5,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(stats.samples, 8);
+        assert_eq!(
+            stats.longest_increase,
+            Some(stats::Run { start: 4, end: 7 })
+        );
+        assert_eq!(
+            stats.longest_decrease,
+            Some(stats::Run { start: 0, end: 1 })
+        );
+        assert_eq!(
+            stats.largest_jumps,
+            vec![
+                stats::Step {
+                    index: 7,
+                    delta: 23
+                },
+                stats::Step {
+                    index: 1,
+                    delta: -17
+                },
+            ]
+        );
+        assert_eq!(
+            stats.histogram.first().map(|b| (b.from, b.count)),
+            Some((-20, 1))
+        );
+        assert_eq!(
+            stats.anomalies.iter().map(|s| s.index).collect::<Vec<_>>(),
+            vec![1, 7]
+        );
+        assert!(stats
+            .to_json()
+            .contains("\"longest_increase\": {\"start\": 4"));
+    }
+
+    #[test]
+    fn command_options() {
+        let data = include_str!("data_small.txt");
+        let run = |name: &str, args: &[&str]| {
+            let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+            day_command(data, name, &args)
+        };
+
+        assert_eq!(run("count", &["--window", "3"]), Ok("4\n".to_string()));
+        assert_eq!(run("count", &["--window", "8"]), Ok("0\n".to_string()));
+        assert_eq!(
+            run("count", &["--window", "0"]),
+            Err("Invalid value for --window: 0".to_string())
+        );
+        assert_eq!(
+            run("stats", &["--window", "9"]),
+            Err("Window of 9 readings is longer than the input, which has 8".to_string())
+        );
+        assert_eq!(
+            run("stats", &["--compare", "decrease"]),
+            Err("stats doesn't take --compare".to_string())
+        );
+        assert_eq!(
+            run("count", &["--top", "3"]),
+            Err("count doesn't take --top".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_modes() {
+        let data = "100\n101\nERR\n\n102";
+        assert_eq!(
+            parse_depths(data, ParseMode::Strict),
+            Err("Invalid depth reading on line 3: \"ERR\"".to_string())
+        );
+        assert_eq!(
+            parse_depths(data, ParseMode::Lenient),
+            Ok(Depths {
+                values: vec![100, 101, 102],
+                skipped: 2
+            })
+        );
+    }
 }
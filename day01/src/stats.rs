@@ -0,0 +1,277 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::window_sums;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatsOptions {
+    pub window: usize,
+    /// How many of the largest jumps to keep
+    pub top: usize,
+    pub bucket_width: i64,
+    /// Steps further than `k` standard deviations from the mean are anomalies
+    pub k: f64,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        StatsOptions {
+            window: 1,
+            top: 5,
+            bucket_width: 10,
+            k: 3.0,
+        }
+    }
+}
+
+/// Indices (inclusive) into the window series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Run {
+    pub fn len(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// Change from the value at `index - 1` to the value at `index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub index: usize,
+    pub delta: i64,
+}
+
+/// Steps with `from <= delta < to`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub from: i64,
+    pub to: i64,
+    pub count: usize,
+}
+
+/// Statistics over the series of window sums. With a window of 1 the series
+/// is the readings themselves and indices are reading indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthStats {
+    pub window: usize,
+    pub samples: usize,
+    pub longest_increase: Option<Run>,
+    pub longest_decrease: Option<Run>,
+    pub largest_jumps: Vec<Step>,
+    pub mean_delta: f64,
+    pub std_delta: f64,
+    pub histogram: Vec<Bucket>,
+    pub anomalies: Vec<Step>,
+}
+
+fn longest_run(steps: &[Step], matches: impl Fn(i64) -> bool) -> Option<Run> {
+    let mut longest: Option<Run> = None;
+    let mut current: Option<Run> = None;
+
+    for step in steps {
+        if !matches(step.delta) {
+            current = None;
+            continue;
+        }
+
+        let run = match current {
+            Some(run) => Run {
+                start: run.start,
+                end: step.index,
+            },
+            None => Run {
+                start: step.index - 1,
+                end: step.index,
+            },
+        };
+
+        if longest.is_none_or(|longest| run.len() > longest.len()) {
+            longest = Some(run);
+        }
+        current = Some(run);
+    }
+
+    longest
+}
+
+pub fn analyse(depths: &[i32], options: &StatsOptions) -> DepthStats {
+    let mut samples = 0;
+    let mut previous = None;
+    let mut steps = Vec::new();
+
+    for (idx, sum) in window_sums(depths, options.window).enumerate() {
+        if let Some(previous) = previous {
+            steps.push(Step {
+                index: idx,
+                delta: sum - previous,
+            });
+        }
+
+        previous = Some(sum);
+        samples += 1;
+    }
+
+    let count = steps.len().max(1) as f64;
+    let mean_delta = steps.iter().map(|step| step.delta as f64).sum::<f64>() / count;
+    let variance = steps
+        .iter()
+        .map(|step| (step.delta as f64 - mean_delta).powi(2))
+        .sum::<f64>()
+        / count;
+    let std_delta = variance.sqrt();
+
+    let mut largest_jumps = steps.clone();
+    // stable sort keeps earlier steps first among equal jumps
+    largest_jumps.sort_by_key(|step| std::cmp::Reverse(step.delta.abs()));
+    largest_jumps.truncate(options.top);
+
+    let mut buckets: BTreeMap<i64, usize> = BTreeMap::new();
+    for step in &steps {
+        *buckets
+            .entry(step.delta.div_euclid(options.bucket_width))
+            .or_insert(0) += 1;
+    }
+
+    let histogram = buckets
+        .into_iter()
+        .map(|(bucket, count)| Bucket {
+            from: bucket * options.bucket_width,
+            to: (bucket + 1) * options.bucket_width,
+            count,
+        })
+        .collect();
+
+    // a perfectly even series has no anomalies
+    let anomalies = steps
+        .iter()
+        .filter(|step| {
+            std_delta > 0.0 && (step.delta as f64 - mean_delta).abs() > options.k * std_delta
+        })
+        .copied()
+        .collect();
+
+    DepthStats {
+        window: options.window,
+        samples,
+        longest_increase: longest_run(&steps, |delta| delta > 0),
+        longest_decrease: longest_run(&steps, |delta| delta < 0),
+        largest_jumps,
+        mean_delta,
+        std_delta,
+        histogram,
+        anomalies,
+    }
+}
+
+fn run_to_string(run: Option<Run>) -> String {
+    match run {
+        Some(run) => format!("{} readings ({}..={})", run.len(), run.start, run.end),
+        None => "none".to_string(),
+    }
+}
+
+fn run_to_json(run: Option<Run>) -> String {
+    match run {
+        Some(run) => format!(
+            "{{\"start\": {}, \"end\": {}, \"length\": {}}}",
+            run.start,
+            run.end,
+            run.len()
+        ),
+        None => "null".to_string(),
+    }
+}
+
+fn steps_to_json(steps: &[Step]) -> String {
+    let entries = steps
+        .iter()
+        .map(|step| format!("{{\"index\": {}, \"delta\": {}}}", step.index, step.delta))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", entries.join(", "))
+}
+
+impl DepthStats {
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "window            {}", self.window).unwrap();
+        writeln!(out, "samples           {}", self.samples).unwrap();
+        writeln!(
+            out,
+            "longest increase  {}",
+            run_to_string(self.longest_increase)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "longest decrease  {}",
+            run_to_string(self.longest_decrease)
+        )
+        .unwrap();
+        writeln!(out, "mean step         {:.3}", self.mean_delta).unwrap();
+        writeln!(out, "std deviation     {:.3}", self.std_delta).unwrap();
+
+        writeln!(out, "\nlargest jumps\n  index      delta").unwrap();
+        for step in &self.largest_jumps {
+            writeln!(out, "  {:<10} {:>+}", step.index, step.delta).unwrap();
+        }
+
+        writeln!(out, "\nhistogram\n  range              count").unwrap();
+        for bucket in &self.histogram {
+            let range = format!("[{}, {})", bucket.from, bucket.to);
+            writeln!(out, "  {:<18} {}", range, bucket.count).unwrap();
+        }
+
+        writeln!(out, "\nanomalies\n  index      delta").unwrap();
+        for step in &self.anomalies {
+            writeln!(out, "  {:<10} {:>+}", step.index, step.delta).unwrap();
+        }
+
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let histogram = self
+            .histogram
+            .iter()
+            .map(|bucket| {
+                format!(
+                    "{{\"from\": {}, \"to\": {}, \"count\": {}}}",
+                    bucket.from, bucket.to, bucket.count
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+        writeln!(out, "{{").unwrap();
+        writeln!(out, "  \"window\": {},", self.window).unwrap();
+        writeln!(out, "  \"samples\": {},", self.samples).unwrap();
+        writeln!(
+            out,
+            "  \"longest_increase\": {},",
+            run_to_json(self.longest_increase)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "  \"longest_decrease\": {},",
+            run_to_json(self.longest_decrease)
+        )
+        .unwrap();
+        writeln!(out, "  \"mean_delta\": {},", self.mean_delta).unwrap();
+        writeln!(out, "  \"std_delta\": {},", self.std_delta).unwrap();
+        writeln!(
+            out,
+            "  \"largest_jumps\": {},",
+            steps_to_json(&self.largest_jumps)
+        )
+        .unwrap();
+        writeln!(out, "  \"histogram\": [{}],", histogram.join(", ")).unwrap();
+        writeln!(out, "  \"anomalies\": {}", steps_to_json(&self.anomalies)).unwrap();
+        writeln!(out, "}}").unwrap();
+
+        out
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    iter::Enumerate,
+    str::{FromStr, Lines},
+};
+
+use crate::Movement;
+
+/// Most commands a course can expand to, so that nested repeats can't
+/// exhaust the memory
+const MAX_COMMANDS: usize = 10_000_000;
+
+/// Parser for course files. Besides plain commands a course can contain:
+///
+/// - comments starting with `#` and blank lines
+/// - `repeat <count> {` ... `}` blocks
+/// - `macro <name> {` ... `}` definitions, expanded wherever `<name>` is
+///   written on its own line after the definition
+///
+/// Blocks can be nested, braces have to be the last word on the line.
+struct Parser<'a> {
+    lines: Enumerate<Lines<'a>>,
+    macros: HashMap<&'a str, Vec<Movement>>,
+}
+
+fn is_command(word: &str) -> bool {
+    matches!(word, "forward" | "back" | "up" | "down" | "left" | "right")
+}
+
+fn is_identifier(word: &str) -> bool {
+    let mut chars = word.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl<'a> Parser<'a> {
+    fn new(data: &'a str) -> Self {
+        Parser {
+            lines: data.lines().enumerate(),
+            macros: HashMap::new(),
+        }
+    }
+
+    /// Parses lines until the closing brace of the block opened on line
+    /// `opened` or, for the top level, until the end of the input
+    fn block(&mut self, opened: Option<usize>) -> Result<Vec<Movement>, String> {
+        let mut movements = Vec::new();
+
+        while let Some((idx, line)) = self.lines.next() {
+            let line_number = idx + 1;
+            let error = |message: String| format!("line {}: {}", line_number, message);
+
+            let line = match line.split_once('#') {
+                Some((code, _)) => code,
+                None => line,
+            };
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                [] => continue,
+                ["}"] => {
+                    return match opened {
+                        Some(_) => Ok(movements),
+                        None => Err(error("Unexpected '}'".to_string())),
+                    }
+                }
+                ["repeat", count, "{"] => {
+                    let count = count
+                        .parse::<usize>()
+                        .map_err(|_| error(format!("Invalid repeat count: {}", count)))?;
+                    let body = self.block(Some(line_number))?;
+
+                    let expanded = body
+                        .len()
+                        .checked_mul(count)
+                        .and_then(|len| len.checked_add(movements.len()))
+                        .filter(|&len| len <= MAX_COMMANDS)
+                        .ok_or_else(|| error("repeat expands to too many commands".to_string()))?;
+
+                    // an empty body repeated any number of times is nothing
+                    let repeated = expanded - movements.len();
+                    movements.extend(body.iter().cycle().take(repeated));
+                }
+                ["macro", name, "{"] => {
+                    if !is_identifier(name)
+                        || is_command(name)
+                        || matches!(*name, "repeat" | "macro")
+                    {
+                        return Err(error(format!("Invalid macro name: {}", name)));
+                    }
+                    if self.macros.contains_key(name) {
+                        return Err(error(format!("Macro already defined: {}", name)));
+                    }
+
+                    let body = self.block(Some(line_number))?;
+                    self.macros.insert(name, body);
+                }
+                [name] if !is_command(name) => match self.macros.get(name) {
+                    Some(body) if movements.len() + body.len() > MAX_COMMANDS => {
+                        return Err(error("macro expands to too many commands".to_string()))
+                    }
+                    Some(body) => movements.extend_from_slice(body),
+                    None => return Err(error(format!("Unknown command or macro: {}", name))),
+                },
+                _ => movements.push(Movement::from_str(line).map_err(error)?),
+            }
+        }
+
+        match opened {
+            Some(line_number) => Err(format!("line {}: Block is never closed", line_number)),
+            None => Ok(movements),
+        }
+    }
+}
+
+/// Parses a course file into the flat list of movements it describes
+pub fn parse_course(data: &str) -> Result<Vec<Movement>, String> {
+    Parser::new(data).block(None)
+}
@@ -1,9 +1,20 @@
-use std::str::FromStr;
+use std::{fmt, fs, process::ExitCode, str::FromStr, time::Duration};
 
-#[derive(Debug)]
+use course::parse_course;
+use steering::{find_model, plan_course, simulate, SteeringModel, Target};
+use trace::Trace;
+use utils::runner::{Command, Runner};
+
+mod course;
+mod steering;
+mod trace;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Movement {
     Vertical(i32),
     Horizontal(i32),
+    /// Sideways, positive is to the right
+    Lateral(i32),
 }
 
 impl FromStr for Movement {
@@ -17,8 +28,9 @@ impl FromStr for Movement {
             None => return Err("Failed to parse direction".to_string()),
         };
 
+        // wider than the movements, so that `back 2147483648` is i32::MIN
         let value = match entries.next() {
-            Some(value_str) => match value_str.parse::<i32>() {
+            Some(value_str) => match value_str.parse::<i64>() {
                 Ok(value) => value,
                 Err(_) => return Err("Failed to parse value".to_string()),
             },
@@ -28,61 +40,188 @@ impl FromStr for Movement {
         if entries.next().is_some() {
             return Err("Too many arguments".to_string());
         }
+        let fits = |signed: i64| {
+            i32::try_from(signed).map_err(|_| format!("Value out of range: {}", value))
+        };
 
         match direction {
-            "forward" => Ok(Movement::Horizontal(value)),
-            "up" => Ok(Movement::Vertical(-value)),
-            "down" => Ok(Movement::Vertical(value)),
+            "forward" => Ok(Movement::Horizontal(fits(value)?)),
+            "back" => Ok(Movement::Horizontal(fits(-value)?)),
+            "up" => Ok(Movement::Vertical(fits(-value)?)),
+            "down" => Ok(Movement::Vertical(fits(value)?)),
+            "left" => Ok(Movement::Lateral(fits(-value)?)),
+            "right" => Ok(Movement::Lateral(fits(value)?)),
             _ => Err("Invalid instruction recieved".to_string()),
         }
     }
 }
 
-fn part1(data: &str) -> Result<i32, String> {
-    let course_instructions: Vec<Movement> = data
-        .lines()
-        .map(|line| Movement::from_str(line).unwrap())
-        .collect();
+impl fmt::Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Movement::Horizontal(value) if value < 0 => {
+                write!(f, "back {}", value.unsigned_abs())
+            }
+            Movement::Horizontal(value) => write!(f, "forward {}", value),
+            Movement::Vertical(value) if value < 0 => write!(f, "up {}", value.unsigned_abs()),
+            Movement::Vertical(value) => write!(f, "down {}", value),
+            Movement::Lateral(value) if value < 0 => write!(f, "left {}", value.unsigned_abs()),
+            Movement::Lateral(value) => write!(f, "right {}", value),
+        }
+    }
+}
+
+fn part1(data: &str) -> Result<i64, String> {
+    let course_instructions = parse_course(data)?;
+    let state = simulate(&steering::Direct, &course_instructions)?;
+
+    println!("pos_x: {}", state.position);
+    println!("pos_y: {}", state.depth);
 
-    let (pos_x, pos_y) = course_instructions
-        .iter()
-        .fold((0, 0), |(x, y), m| match m {
-            Movement::Horizontal(value) => (x + value, y),
-            Movement::Vertical(value) => (x, y + value),
-        });
+    Ok(state.position as i64 * state.depth as i64)
+}
+
+fn part2(data: &str) -> Result<i64, String> {
+    let course_instructions = parse_course(data)?;
+    let state = simulate(&steering::Aim, &course_instructions)?;
 
-    println!("pos_x: {}", pos_x);
-    println!("pos_y: {}", pos_y);
+    Ok(state.position as i64 * state.depth as i64)
+}
 
-    Ok(pos_x * pos_y)
+struct Options {
+    model: &'static (dyn SteeringModel + Sync),
+    input: Option<String>,
+    csv: Option<String>,
+    deeper_than: Option<i32>,
+    target: Target,
 }
 
-fn part2(data: &str) -> Result<i32, String> {
-    let course_instructions: Vec<Movement> = data
-        .lines()
-        .map(|line| Movement::from_str(line).unwrap())
-        .collect();
+/// Arguments shared by the `simulate` and `trace` commands:
+///
+/// `[--model direct|aim|3d] [--input <course file>]`
+/// `[--csv <file>] [--deeper-than <depth>]` (trace)
+/// `[--position <x>] [--depth <y>] [--lateral <z>]` (plan)
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        model: &steering::Aim,
+        input: None,
+        csv: None,
+        deeper_than: None,
+        target: Target::default(),
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        let number = |value: &String| {
+            value
+                .parse::<i32>()
+                .map_err(|_| format!("Invalid value for {}: {}", arg, value))
+        };
 
-    let (pos_x, pos_y, _) = course_instructions
-        .iter()
-        .fold((0, 0, 0), |(pos_x, pos_y, aim), m| match m {
-            Movement::Horizontal(value) => (pos_x + value, pos_y + value * aim, aim),
-            Movement::Vertical(value) => (pos_x, pos_y, aim + value),
-        });
+        match arg.as_str() {
+            "--model" => options.model = find_model(value()?)?,
+            "--input" => options.input = Some(Runner::read_input(value()?)?),
+            "--csv" => options.csv = Some(value()?.clone()),
+            "--deeper-than" => options.deeper_than = Some(number(value()?)?),
+            "--position" => options.target.position = number(value()?)?,
+            "--depth" => options.target.depth = number(value()?)?,
+            "--lateral" => options.target.lateral = number(value()?)?,
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
 
-    Ok(pos_x * pos_y)
+    Ok(options)
 }
 
-fn main() {
+fn day_command(data: &str, name: &str, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args)?;
+    let course = parse_course(options.input.as_deref().unwrap_or(data))?;
+
+    match name {
+        "simulate" => {
+            let state = simulate(options.model, &course)?;
+            Ok(format!(
+                "model: {}\nposition: {}\ndepth: {}\naim: {}\nlateral: {}\n",
+                options.model.name(),
+                state.position,
+                state.depth,
+                state.aim,
+                state.lateral
+            ))
+        }
+        "trace" => {
+            let trace = Trace::record(options.model, &course)?;
+
+            if let Some(path) = &options.csv {
+                fs::write(path, trace.to_csv())
+                    .map_err(|err| format!("Failed to write {}: {}", path, err))?;
+            }
+
+            let describe = |entry: Option<&trace::Entry>| match entry {
+                Some(entry) => format!(
+                    "{} after command #{} ({})",
+                    entry.state.depth, entry.index, entry.movement
+                ),
+                None => "never".to_string(),
+            };
+
+            let mut out = format!(
+                "model: {}\ncommands: {}\nmax depth: {}\ntotal distance: {:.3}\n",
+                options.model.name(),
+                trace.entries.len(),
+                describe(trace.max_depth()),
+                trace.total_distance()
+            );
+            if let Some(depth) = options.deeper_than {
+                out += &format!(
+                    "deeper than {}: {}\n",
+                    depth,
+                    describe(trace.first_deeper_than(depth))
+                );
+            }
+
+            Ok(out)
+        }
+        "plan" => {
+            let course = plan_course(options.model, options.target)?;
+            Ok(course
+                .iter()
+                .map(|movement| format!("{movement}\n"))
+                .collect())
+        }
+        _ => Err(format!("Unknown command: {}", name)),
+    }
+}
+
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
-    let result1 = part1(data).expect("Failed to calculate result for part 1");
-    println!("result #1: {}", result1);
-    let result2 = part2(data).expect("Failed to calculate result for part 1");
-    println!("result #2: {}", result2);
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match runner.command() {
+        Command::Day { name, args } => runner.print_day_output(day_command(data, &name, &args)),
+        _ => {
+            runner.run_fallible("result #1", move |_| part1(data));
+            runner.run_fallible("result #2", move |_| part2(data));
+        }
+    }
+
+    runner.finish()
 }
 
 #[cfg(test)]
 mod test {
+    use crate::{
+        course::parse_course,
+        steering::{find_model, simulate},
+    };
+
     #[test]
     fn part1() {
         let result = crate::part1(include_str!("data_small.txt")).unwrap();
@@ -94,4 +233,209 @@ mod test {
         let result = crate::part2(include_str!("data_small.txt")).unwrap();
         assert_eq!(result, 900);
     }
+
+    #[test]
+    fn course_language() {
+        use crate::Movement::*;
+
+        let course = "\
+            # dive twice, then come back up\n\
+            macro dive {\n\
+                down 2 # steeply\n\
+                forward 1\n\
+            }\n\
+            \n\
+            repeat 2 {\n\
+                dive\n\
+                repeat 0 {\n\
+                    up 100\n\
+                }\n\
+            }\n\
+            back 3\n";
+
+        assert_eq!(
+            parse_course(course),
+            Ok(vec![
+                Vertical(2),
+                Horizontal(1),
+                Vertical(2),
+                Horizontal(1),
+                Horizontal(-3)
+            ])
+        );
+    }
+
+    #[test]
+    fn steering_models() {
+        use crate::steering::State;
+
+        let course = parse_course("forward 5\ndown 5\nright 3\nforward 2\nleft 1").unwrap();
+        let states = ["direct", "aim", "3d"]
+            .map(|name| simulate(find_model(name).unwrap(), &course).unwrap());
+
+        assert_eq!(
+            states,
+            [
+                State {
+                    position: 7,
+                    depth: 5,
+                    aim: 0,
+                    lateral: 0
+                },
+                State {
+                    position: 7,
+                    depth: 10,
+                    aim: 5,
+                    lateral: 0
+                },
+                State {
+                    position: 7,
+                    depth: 10,
+                    aim: 5,
+                    lateral: 2
+                },
+            ]
+        );
+        assert!(find_model("hover").is_err());
+
+        // the dive is deeper than an i32, moving along the surface is fine
+        let course = parse_course("forward 2147483647\ndown 2\nforward 2").unwrap();
+        assert_eq!(
+            simulate(find_model("aim").unwrap(), &course),
+            Err("Command #2 (forward 2) overflows the aim model's state".to_string())
+        );
+        assert!(crate::trace::Trace::record(find_model("3d").unwrap(), &course).is_err());
+        assert_eq!(
+            crate::part1("forward 2147483647\ndown 2147483647"),
+            Ok(2147483647 * 2147483647)
+        );
+        assert!(crate::part1("forward 2147483647\nforward 1").is_err());
+    }
+
+    #[test]
+    fn course_trace() {
+        use crate::{steering::Aim, trace::Trace};
+
+        let course = parse_course(include_str!("data_small.txt")).unwrap();
+        let trace = Trace::record(&Aim, &course).unwrap();
+
+        assert_eq!(trace.entries.len(), 6);
+        assert_eq!(
+            trace.entries.last().map(|entry| entry.state),
+            simulate(&Aim, &course).ok()
+        );
+        assert_eq!(trace.first_deeper_than(0).map(|entry| entry.index), Some(2));
+        assert_eq!(
+            trace
+                .max_depth()
+                .map(|entry| (entry.index, entry.state.depth)),
+            Some((5, 60))
+        );
+        assert!(trace.total_distance() > 0.0);
+        assert_eq!(
+            trace.to_csv().lines().take(3).collect::<Vec<_>>(),
+            vec![
+                "index,command,position,depth,aim,lateral",
+                "0,forward 5,5,0,0,0",
+                "1,down 5,5,0,5,0"
+            ]
+        );
+    }
+
+    #[test]
+    fn planned_courses() {
+        use crate::steering::{plan_course, Target, MODELS};
+
+        let target = |position, depth, lateral| Target {
+            position,
+            depth,
+            lateral,
+        };
+        let plan = |model: &str, target| {
+            plan_course(find_model(model).unwrap(), target).map(|course| course.len())
+        };
+
+        assert_eq!(plan("direct", target(15, 10, 0)), Ok(2));
+        assert_eq!(plan("aim", target(0, 0, 0)), Ok(0));
+        assert_eq!(plan("aim", target(15, 60, 0)), Ok(2));
+        assert_eq!(plan("aim", target(15, 61, 0)), Ok(3));
+        assert_eq!(plan("aim", target(0, -7, 0)), Ok(3));
+        assert_eq!(plan("3d", target(15, 60, -4)), Ok(3));
+        assert!(plan("aim", target(1, 1, 1)).is_err());
+
+        // plan_course simulates every plan, so reaching the target is checked.
+        // Printed plans read back as the same course.
+        let extremes = [i32::MIN, -7, -1, 0, 1, 12, i32::MAX];
+        for model in MODELS {
+            for position in extremes {
+                for depth in extremes {
+                    let course = plan_course(model, target(position, depth, 0));
+                    let printed = course.iter().flatten().map(|m| format!("{m}\n"));
+                    assert_eq!(
+                        parse_course(&printed.collect::<String>()).ok(),
+                        course.ok(),
+                        "{} {} {}",
+                        model.name(),
+                        position,
+                        depth
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            crate::Movement::Horizontal(i32::MIN).to_string(),
+            "back 2147483648"
+        );
+    }
+
+    #[test]
+    fn course_errors() {
+        assert_eq!(
+            parse_course("forward 1\nsideways 2"),
+            Err("line 2: Invalid instruction recieved".to_string())
+        );
+        assert_eq!(
+            parse_course("dive\nmacro dive {\n}"),
+            Err("line 1: Unknown command or macro: dive".to_string())
+        );
+        assert_eq!(
+            parse_course("forward 1\nrepeat 3 {\n  down 1\n"),
+            Err("line 2: Block is never closed".to_string())
+        );
+        assert_eq!(
+            parse_course("repeat -1 {\n}"),
+            Err("line 1: Invalid repeat count: -1".to_string())
+        );
+        assert_eq!(
+            parse_course("up 1\n}"),
+            Err("line 2: Unexpected '}'".to_string())
+        );
+
+        assert_eq!(
+            parse_course("forward 1\nrepeat 4000000000 {\n  down 1\n}"),
+            Err("line 2: repeat expands to too many commands".to_string())
+        );
+        assert_eq!(
+            parse_course("repeat 100000 {\n  repeat 100000 {\n    up 1\n  }\n}"),
+            Err("line 1: repeat expands to too many commands".to_string())
+        );
+        assert_eq!(
+            parse_course(&format!("repeat {} {{\n  up 1\n}}", usize::MAX)),
+            Err("line 1: repeat expands to too many commands".to_string())
+        );
+        assert_eq!(
+            parse_course("repeat 1000 {\n  up 1\n}").map(|c| c.len()),
+            Ok(1000)
+        );
+        assert_eq!(parse_course("repeat 4000000000 {\n}"), Ok(vec![]));
+
+        assert_eq!(
+            parse_course("down 1\nback -2147483648"),
+            Err("line 2: Value out of range: -2147483648".to_string())
+        );
+        assert_eq!(
+            parse_course("up -2147483647"),
+            Ok(vec![crate::Movement::Vertical(i32::MAX)])
+        );
+    }
 }
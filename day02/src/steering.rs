@@ -0,0 +1,227 @@
+use crate::Movement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct State {
+    pub position: i32,
+    pub depth: i32,
+    pub aim: i32,
+    /// Offset to the right of the starting line, only used by the 3D model
+    pub lateral: i32,
+}
+
+/// Where a planned course has to end, the aim is left free
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Target {
+    pub position: i32,
+    pub depth: i32,
+    pub lateral: i32,
+}
+
+/// How a submarine interprets the shared command stream
+pub trait SteeringModel {
+    fn name(&self) -> &'static str;
+
+    /// `None` when the state doesn't fit an `i32` anymore
+    fn apply(&self, state: State, movement: Movement) -> Option<State>;
+
+    /// Shortest course ending exactly at `target`, `None` if the model can't
+    /// reach it
+    fn plan(&self, target: Target) -> Option<Vec<Movement>>;
+}
+
+/// `up`/`down` change the depth directly. Lateral movement is ignored.
+pub struct Direct;
+
+impl SteeringModel for Direct {
+    fn name(&self) -> &'static str {
+        "direct"
+    }
+
+    fn apply(&self, state: State, movement: Movement) -> Option<State> {
+        Some(match movement {
+            Movement::Horizontal(value) => State {
+                position: state.position.checked_add(value)?,
+                ..state
+            },
+            Movement::Vertical(value) => State {
+                depth: state.depth.checked_add(value)?,
+                ..state
+            },
+            Movement::Lateral(_) => state,
+        })
+    }
+
+    fn plan(&self, target: Target) -> Option<Vec<Movement>> {
+        if target.lateral != 0 {
+            return None;
+        }
+
+        let mut course = Vec::new();
+        if target.position != 0 {
+            course.push(Movement::Horizontal(target.position));
+        }
+        if target.depth != 0 {
+            course.push(Movement::Vertical(target.depth));
+        }
+        Some(course)
+    }
+}
+
+/// `up`/`down` change the aim, moving forward dives along it. Lateral
+/// movement is ignored.
+pub struct Aim;
+
+impl SteeringModel for Aim {
+    fn name(&self) -> &'static str {
+        "aim"
+    }
+
+    fn apply(&self, state: State, movement: Movement) -> Option<State> {
+        Some(match movement {
+            Movement::Horizontal(value) => State {
+                position: state.position.checked_add(value)?,
+                depth: state.depth.checked_add(value.checked_mul(state.aim)?)?,
+                ..state
+            },
+            Movement::Vertical(value) => State {
+                aim: state.aim.checked_add(value)?,
+                ..state
+            },
+            Movement::Lateral(_) => state,
+        })
+    }
+
+    // Depth only changes while moving forward with a non-zero aim, so a dive
+    // needs at least an aim change followed by a move. That is enough when
+    // the position divides the depth, otherwise a first move gets the
+    // position right and a final move of one (or minus one) unit the depth.
+    fn plan(&self, target: Target) -> Option<Vec<Movement>> {
+        let Target {
+            position, depth, ..
+        } = target;
+
+        if target.lateral != 0 {
+            return None;
+        }
+        if depth == 0 {
+            return Some(match position {
+                0 => vec![],
+                _ => vec![Movement::Horizontal(position)],
+            });
+        }
+        if let (Some(0), Some(aim)) = (depth.checked_rem(position), depth.checked_div(position)) {
+            return Some(vec![
+                Movement::Vertical(aim),
+                Movement::Horizontal(position),
+            ]);
+        }
+
+        [1, -1].into_iter().find_map(|last: i32| {
+            Some(vec![
+                Movement::Horizontal(position.checked_sub(last)?),
+                Movement::Vertical(depth.checked_div(last)?),
+                Movement::Horizontal(last),
+            ])
+        })
+    }
+}
+
+/// The aim model with `left`/`right` strafing sideways
+pub struct Lateral;
+
+impl SteeringModel for Lateral {
+    fn name(&self) -> &'static str {
+        "3d"
+    }
+
+    fn apply(&self, state: State, movement: Movement) -> Option<State> {
+        match movement {
+            Movement::Lateral(value) => Some(State {
+                lateral: state.lateral.checked_add(value)?,
+                ..state
+            }),
+            _ => Aim.apply(state, movement),
+        }
+    }
+
+    fn plan(&self, target: Target) -> Option<Vec<Movement>> {
+        let mut course = Aim.plan(Target {
+            lateral: 0,
+            ..target
+        })?;
+
+        if target.lateral != 0 {
+            course.push(Movement::Lateral(target.lateral));
+        }
+        Some(course)
+    }
+}
+
+pub const MODELS: [&(dyn SteeringModel + Sync); 3] = [&Direct, &Aim, &Lateral];
+
+pub fn find_model(name: &str) -> Result<&'static (dyn SteeringModel + Sync), String> {
+    MODELS
+        .into_iter()
+        .find(|model| model.name() == name)
+        .ok_or_else(|| {
+            let names = MODELS.map(|model| model.name());
+            format!(
+                "Unknown model: {} (expected one of {})",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+/// Applies the command at `index` of a course, failing when it overflows
+pub fn step(
+    model: &dyn SteeringModel,
+    state: State,
+    index: usize,
+    movement: Movement,
+) -> Result<State, String> {
+    model.apply(state, movement).ok_or_else(|| {
+        format!(
+            "Command #{} ({}) overflows the {} model's state",
+            index,
+            movement,
+            model.name()
+        )
+    })
+}
+
+pub fn simulate(model: &dyn SteeringModel, course: &[Movement]) -> Result<State, String> {
+    course
+        .iter()
+        .enumerate()
+        .try_fold(State::default(), |state, (index, &movement)| {
+            step(model, state, index, movement)
+        })
+}
+
+/// Plans a course for `model` and checks it by simulating it
+pub fn plan_course(model: &dyn SteeringModel, target: Target) -> Result<Vec<Movement>, String> {
+    let course = model.plan(target).ok_or_else(|| {
+        format!(
+            "Target {:?} can't be reached with the {} model",
+            target,
+            model.name()
+        )
+    })?;
+
+    let state = simulate(model, &course)?;
+    let reached = Target {
+        position: state.position,
+        depth: state.depth,
+        lateral: state.lateral,
+    };
+
+    if reached != target {
+        return Err(format!(
+            "Planned course ends at {:?} instead of {:?}",
+            reached, target
+        ));
+    }
+
+    Ok(course)
+}
@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use crate::{
+    steering::{self, State, SteeringModel},
+    Movement,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// Index of the command in the expanded course
+    pub index: usize,
+    pub movement: Movement,
+    /// State after the command was applied
+    pub state: State,
+}
+
+/// Submarine state after every command of a course
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub entries: Vec<Entry>,
+}
+
+impl Trace {
+    pub fn record(model: &dyn SteeringModel, course: &[Movement]) -> Result<Self, String> {
+        let mut state = State::default();
+        let entries = course
+            .iter()
+            .enumerate()
+            .map(|(index, &movement)| {
+                state = steering::step(model, state, index, movement)?;
+                Ok(Entry {
+                    index,
+                    movement,
+                    state,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Trace { entries })
+    }
+
+    /// First command after which the submarine is deeper than `depth`
+    pub fn first_deeper_than(&self, depth: i32) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.state.depth > depth)
+    }
+
+    /// Deepest point of the course, the earliest one if it is reached twice
+    pub fn max_depth(&self) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .rev()
+            .max_by_key(|entry| entry.state.depth)
+    }
+
+    /// Length of the path through the water. Changing the aim doesn't move
+    /// the submarine.
+    pub fn total_distance(&self) -> f64 {
+        let mut previous = State::default();
+
+        self.entries
+            .iter()
+            .map(|entry| {
+                let dx = (entry.state.position - previous.position) as f64;
+                let dy = (entry.state.depth - previous.depth) as f64;
+                let dz = (entry.state.lateral - previous.lateral) as f64;
+                previous = entry.state;
+                (dx * dx + dy * dy + dz * dz).sqrt()
+            })
+            .sum()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("index,command,position,depth,aim,lateral\n");
+
+        for entry in &self.entries {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                entry.index,
+                entry.movement,
+                entry.state.position,
+                entry.state.depth,
+                entry.state.aim,
+                entry.state.lateral
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
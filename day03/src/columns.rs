@@ -0,0 +1,169 @@
+use bitvec::prelude::*;
+use num_bigint::BigUint;
+use utils::bits::BitsError;
+
+use crate::{
+    bits_to_biguint,
+    rating::{Rating, RatingPolicy, Round},
+    PowerReport,
+};
+
+/// The report stored column by column: bit `r % 64` of word `r / 64` in
+/// column `c` is bit `c` of row `r`. Counting the ones of a column, or of a
+/// subset of the rows, is then a popcount over its words.
+pub struct Columns {
+    rows: usize,
+    columns: Vec<Vec<u64>>,
+}
+
+impl Columns {
+    /// Reads the report straight into columns, without going through a
+    /// vector per row. Errors are the same as for `parse_report`.
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let mut rows = 0;
+        let mut columns: Vec<Vec<u64>> = Vec::new();
+        // every byte before `index` is a 0 or a 1, so it's a char boundary
+        let invalid = |line: &str, index: usize| {
+            let found = line[index..].chars().next().unwrap_or_default();
+            let err = BitsError::InvalidDigit { index, found };
+            format!("Failed to parse measurement {}: {}", line, err)
+        };
+        let invalid_index = |digits: &[u8]| digits.iter().position(|&d| d != b'0' && d != b'1');
+
+        for (idx, line) in data.lines().enumerate() {
+            let digits = line.as_bytes();
+            if idx == 0 {
+                if digits.is_empty() {
+                    return Err("Row 1 is empty".to_string());
+                }
+                columns = vec![Vec::new(); digits.len()];
+            } else if digits.len() != columns.len() {
+                if let Some(index) = invalid_index(digits) {
+                    return Err(invalid(line, index));
+                }
+                return Err(format!(
+                    "Row {} has {} bits, expected {} like row 1",
+                    idx + 1,
+                    digits.len(),
+                    columns.len()
+                ));
+            }
+
+            if idx % 64 == 0 {
+                columns.iter_mut().for_each(|words| words.push(0));
+            }
+            if let Some(index) = invalid_index(digits) {
+                return Err(invalid(line, index));
+            }
+            for (words, &digit) in columns.iter_mut().zip(digits) {
+                if digit == b'1' {
+                    words[idx / 64] |= 1 << (idx % 64);
+                }
+            }
+            rows += 1;
+        }
+
+        if rows == 0 {
+            return Err("Diagnostic report is empty".to_string());
+        }
+
+        Ok(Columns { rows, columns })
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn ones(&self, column: usize) -> usize {
+        self.columns[column]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn zeros(&self, column: usize) -> usize {
+        self.rows - self.ones(column)
+    }
+
+    pub fn row(&self, idx: usize) -> BitVec<u32, Msb0> {
+        self.columns
+            .iter()
+            .map(|words| (words[idx / 64] >> (idx % 64)) & 1 == 1)
+            .collect()
+    }
+
+    /// Mask with a bit set for every row
+    fn all_rows(&self) -> Vec<u64> {
+        let mut mask = vec![u64::MAX; self.rows.div_ceil(64)];
+        if let Some(last) = mask.last_mut() {
+            *last >>= (64 - self.rows % 64) % 64;
+        }
+        mask
+    }
+
+    pub fn power(&self) -> PowerReport {
+        let gamma_bits = (0..self.width())
+            .map(|column| self.zeros(column) <= self.ones(column))
+            .collect::<BitVec<u32, Msb0>>();
+
+        PowerReport::from_gamma_bits(gamma_bits)
+    }
+
+    /// The survivors are kept as a bitmask over the rows, so each round
+    /// counts them with a popcount of the mask against the column and keeps
+    /// a bit by masking, a word at a time instead of a pass over the rows.
+    /// They are the rows sharing a prefix, like the range `SortedRows`
+    /// narrows down, without having to sort the rows first.
+    pub fn rating(&self, policy: RatingPolicy) -> Rating {
+        let mut survivors = self.all_rows();
+        let mut count = self.rows;
+        let mut rounds = Vec::new();
+
+        for (idx, column) in self.columns.iter().enumerate() {
+            if count <= 1 {
+                break;
+            }
+
+            let ones = survivors
+                .iter()
+                .zip(column)
+                .map(|(mask, bits)| (mask & bits).count_ones() as usize)
+                .sum::<usize>();
+            let zeros = count - ones;
+
+            let keep_ones = policy.bit_to_keep(zeros, ones);
+            for (mask, bits) in survivors.iter_mut().zip(column) {
+                *mask &= if keep_ones { *bits } else { !bits };
+            }
+            count = if keep_ones { ones } else { zeros };
+
+            rounds.push(Round {
+                column: idx,
+                zeros,
+                ones,
+                kept: keep_ones,
+                survivors: count,
+            });
+        }
+
+        // the earliest row among duplicates, like the retain based filter
+        let (word, mask) = survivors
+            .iter()
+            .enumerate()
+            .find(|(_, mask)| **mask != 0)
+            .expect("At least one row survives every round");
+        let row = self.row(word * 64 + mask.trailing_zeros() as usize);
+
+        Rating {
+            value: bits_to_biguint(&row),
+            rounds,
+        }
+    }
+
+    pub fn life_support(&self) -> BigUint {
+        let oxygen = self.rating(RatingPolicy::OXYGEN);
+        let co2 = self.rating(RatingPolicy::CO2);
+
+        oxygen.value * co2.value
+    }
+}
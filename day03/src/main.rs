@@ -1,52 +1,178 @@
+use std::{process::ExitCode, time::Duration};
+
 use bitvec::prelude::*;
+use num_bigint::BigUint;
+use utils::{
+    bits,
+    registry::{self, Implementations},
+    rng::Rng,
+    runner::{Command, Runner},
+};
 
-fn main() {
-    let data = include_str!("data.txt");
-    let result1 = match part1(data) {
-        Ok(result) => result,
-        Err(err) => panic!("Failed to calculate result for part 1: {}", err),
-    };
+mod columns;
+mod rating;
+mod sorted;
 
-    println!("result #1: {}", result1);
+use columns::Columns;
+use rating::{Rating, RatingPolicy, Round};
+use sorted::SortedRows;
 
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
-    let result2 = match part2(data) {
-        Ok(result) => result,
-        Err(err) => panic!("Failed to calculate result for part 1: {}", err),
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
     };
 
-    println!("result #2: {}", result2);
+    match runner.command() {
+        Command::Solve | Command::Report { .. } => {
+            runner.run_fallible("result #1", move |_| part1(data));
+
+            runner.run_fallible("result #2", move |_| part2(data));
+        }
+        Command::Diff { samples, seed } => {
+            let mut rng = Rng::new(seed);
+            let mut inputs = vec![data.to_string()];
+            inputs.extend((0..samples).map(|_| generate_input(&mut rng)));
+
+            let parts = [&part1_implementations(), &part2_implementations()];
+            if registry::diff::<str, _, _>(&parts, &inputs) > 0 {
+                runner.mark_failed();
+            }
+        }
+        Command::Day { name, args } => runner.print_day_output(day_command(data, &name, &args)),
+    }
+
+    runner.finish()
+}
+
+/// Rows of a diagnostic report, all of them `width` bits wide
+struct Report {
+    width: usize,
+    rows: Vec<BitVec<u32, Msb0>>,
 }
 
-fn bits_slice_to_u32(bits: &BitVec<u32, Msb0>) -> u32 {
-    bits.iter().fold(0, |result, bit| {
-        let bit_value = if *bit { 1 } else { 0 };
-        (result << 1) | bit_value
-    })
+/// Ratings are as wide as the report, so they are kept as big integers.
+/// `u128::try_from(&rating)` gets a fixed width value when one is needed.
+fn bits_to_biguint(bits: &BitSlice<u32, Msb0>) -> BigUint {
+    bits.iter()
+        .by_vals()
+        .fold(BigUint::default(), |value, bit| {
+            (value << 1u8) | BigUint::from(bit as u8)
+        })
 }
 
-fn str_to_bit_array(input: &str) -> BitVec<u32, Msb0> {
-    let mut bit_vec = bitvec![u32, Msb0;];
+/// Gamma has the most common bit of every column, 1 on a tie, and epsilon
+/// the least common one
+#[derive(Debug, PartialEq, Eq)]
+struct PowerReport {
+    gamma: BigUint,
+    epsilon: BigUint,
+}
+
+impl PowerReport {
+    fn from_gamma_bits(gamma_bits: BitVec<u32, Msb0>) -> Self {
+        PowerReport {
+            gamma: bits_to_biguint(&gamma_bits),
+            epsilon: bits_to_biguint(&!gamma_bits),
+        }
+    }
+
+    fn consumption(&self) -> BigUint {
+        &self.gamma * &self.epsilon
+    }
+}
 
-    input.chars().for_each(|char| {
-        let bit: bool = match char {
-            '0' => false,
-            '1' => true,
-            _ => panic!("Invalid character found when parsing measurement: {}", char),
-        };
-        bit_vec.push(bit);
-    });
+fn str_to_bit_array(input: &str) -> Result<BitVec<u32, Msb0>, String> {
+    let bits = bits::parse_binary(input)
+        .map_err(|err| format!("Failed to parse measurement {}: {}", input, err))?;
 
-    bit_vec
+    Ok(bits.into_iter().collect())
 }
 
-fn part1(data: &str) -> Result<u32, String> {
-    let bit_lines: Vec<BitVec<u32, Msb0>> = data.lines().map(str_to_bit_array).collect();
+fn parse_report(data: &str) -> Result<Report, String> {
+    let mut rows: Vec<BitVec<u32, Msb0>> = Vec::new();
+
+    for (idx, line) in data.lines().enumerate() {
+        let row = str_to_bit_array(line)?;
+
+        match rows.first() {
+            None if row.is_empty() => return Err("Row 1 is empty".to_string()),
+            Some(first) if first.len() != row.len() => {
+                return Err(format!(
+                    "Row {} has {} bits, expected {} like row 1",
+                    idx + 1,
+                    row.len(),
+                    first.len()
+                ))
+            }
+            _ => rows.push(row),
+        }
+    }
 
+    match rows.first() {
+        Some(first) => Ok(Report {
+            width: first.len(),
+            rows,
+        }),
+        None => Err("Diagnostic report is empty".to_string()),
+    }
+}
+
+fn generate_input(rng: &mut Rng) -> String {
+    // up to 70 bits so that rows span more than one word
+    let width = rng.gen_range(1..71);
+    let rows = (0..rng.gen_range(1..300))
+        .map(|_| {
+            (0..width)
+                .map(|_| if rng.gen_range(0..2) == 1 { '1' } else { '0' })
+                .collect()
+        })
+        .collect::<Vec<String>>();
+
+    rows.join("\n")
+}
+
+/// The column based parts are the fast path, the row based ones are the
+/// original solutions kept to check them. Part 2 can also filter a sorted
+/// copy of the rows, but sorting a million rows takes about a second where
+/// the column based filter takes under a millisecond once parsed.
+fn part1_implementations() -> Implementations<str, Result<BigUint, String>> {
+    Implementations::new("result #1")
+        .register("popcount", part1)
+        .register("fold", |data| {
+            parse_report(data).map(|report| power(&report).consumption())
+        })
+}
+
+fn part2_implementations() -> Implementations<str, Result<BigUint, String>> {
+    Implementations::new("result #2")
+        .register("bitmask", part2)
+        .register("sorted", |data| {
+            parse_report(data).map(|report| SortedRows::new(report).life_support())
+        })
+        .register("retain", |data| {
+            parse_report(data).map(|report| life_support(&report))
+        })
+}
+
+fn part1(data: &str) -> Result<BigUint, String> {
+    Ok(Columns::parse(data)?.power().consumption())
+}
+
+fn part2(data: &str) -> Result<BigUint, String> {
+    Ok(Columns::parse(data)?.life_support())
+}
+
+fn power(report: &Report) -> PowerReport {
     let mut gamma_bits = bitvec![u32, Msb0;];
 
-    for i in 0..bit_lines[0].len() {
-        let counts = bit_lines
+    for i in 0..report.width {
+        let counts = report
+            .rows
             .iter()
             .fold((0, 0), |(zeros, ones), line| match line[i] {
                 true => (zeros, ones + 1),
@@ -56,27 +182,21 @@ fn part1(data: &str) -> Result<u32, String> {
         gamma_bits.push(counts.0 <= counts.1);
     }
 
-    let gamma = bits_slice_to_u32(&gamma_bits);
-    let mut epsilon_bits = BitVec::<u32, Msb0>::repeat(true, gamma_bits.len());
-    epsilon_bits ^= gamma_bits;
-    let epsilon = bits_slice_to_u32(&epsilon_bits);
-
-    Ok(gamma * epsilon)
+    PowerReport::from_gamma_bits(gamma_bits)
 }
 
-fn part2(data: &str) -> Result<u32, String> {
-    let bit_lines: Vec<BitVec<u32, Msb0>> = data.lines().map(str_to_bit_array).collect();
+fn life_support(report: &Report) -> BigUint {
+    let oxygen_list: Vec<&BitVec<u32, Msb0>> = report.rows.iter().collect();
+    let oxygen = find_rating(oxygen_list, RatingPolicy::OXYGEN);
+    let co2_list: Vec<&BitVec<u32, Msb0>> = report.rows.iter().collect();
+    let co2 = find_rating(co2_list, RatingPolicy::CO2);
 
-    let oxygen_list: Vec<&BitVec<u32, Msb0>> = bit_lines.iter().collect();
-    let oxygen = find_oxygen_measurement(oxygen_list, true);
-    let co2_list: Vec<&BitVec<u32, Msb0>> = bit_lines.iter().collect();
-    let co2 = find_oxygen_measurement(co2_list, false);
-
-    Ok(oxygen * co2)
+    oxygen.value * co2.value
 }
 
-fn find_oxygen_measurement(mut list: Vec<&BitVec<u32, Msb0>>, positive_bias: bool) -> u32 {
+fn find_rating(mut list: Vec<&BitVec<u32, Msb0>>, policy: RatingPolicy) -> Rating {
     let mut current_idx = 0;
+    let mut rounds = Vec::new();
 
     while list.len() > 1 && current_idx < list[0].len() {
         let counts = list.iter().fold((0, 0), |(zeros, ones), line| {
@@ -88,13 +208,87 @@ fn find_oxygen_measurement(mut list: Vec<&BitVec<u32, Msb0>>, positive_bias: boo
             }
         });
 
-        let target_value = (counts.0 > counts.1) ^ positive_bias;
-
+        let target_value = policy.bit_to_keep(counts.0, counts.1);
         list.retain(|&line| line[current_idx] == target_value);
+
+        rounds.push(Round {
+            column: current_idx,
+            zeros: counts.0,
+            ones: counts.1,
+            kept: target_value,
+            survivors: list.len(),
+        });
         current_idx += 1;
     }
 
-    bits_slice_to_u32(list[0])
+    Rating {
+        value: bits_to_biguint(list[0]),
+        rounds,
+    }
+}
+
+struct Options {
+    oxygen: RatingPolicy,
+    co2: RatingPolicy,
+    input: Option<String>,
+}
+
+/// `[--oxygen <policy>] [--co2 <policy>] [--input <file>]`, policies being
+/// `most:1`, `most:0`, `least:1` or `least:0`
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        oxygen: RatingPolicy::OXYGEN,
+        co2: RatingPolicy::CO2,
+        input: None,
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+
+        match arg.as_str() {
+            "--oxygen" => options.oxygen = value()?.parse()?,
+            "--co2" => options.co2 = value()?.parse()?,
+            "--input" => options.input = Some(Runner::read_input(value()?)?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn day_command(data: &str, name: &str, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args)?;
+    let columns = Columns::parse(options.input.as_deref().unwrap_or(data))?;
+
+    match name {
+        "power" => {
+            let power = columns.power();
+
+            Ok(format!(
+                "gamma: {}\nepsilon: {}\npower consumption: {}\n",
+                power.gamma,
+                power.epsilon,
+                power.consumption()
+            ))
+        }
+        "ratings" => {
+            let oxygen = columns.rating(options.oxygen);
+            let co2 = columns.rating(options.co2);
+
+            Ok(format!(
+                "oxygen ({}): {}\n{}\nco2 ({}): {}\n{}\nlife support: {}\n",
+                options.oxygen,
+                oxygen.value,
+                oxygen.trace(),
+                options.co2,
+                co2.value,
+                co2.trace(),
+                &oxygen.value * &co2.value
+            ))
+        }
+        _ => Err(format!("Unknown command: {}", name)),
+    }
 }
 
 #[cfg(test)]
@@ -102,11 +296,128 @@ mod test {
     #[test]
     fn part1() {
         let result = crate::part1(include_str!("data_small.txt")).unwrap();
-        assert_eq!(result, 198);
+        assert_eq!(result, 198u32.into());
     }
     #[test]
     fn part2() {
         let result = crate::part2(include_str!("data_small.txt")).unwrap();
-        assert_eq!(result, 230);
+        assert_eq!(result, 230u32.into());
+    }
+
+    #[test]
+    fn wide_rows() {
+        // 100 bit rows, gamma is 1010... and epsilon 0101...
+        let data = ["10".repeat(50), "10".repeat(50), "01".repeat(50)].join("\n");
+        let expected = "357097343168664505675991576075250511404832563328844317531250"
+            .parse::<num_bigint::BigUint>()
+            .unwrap();
+
+        assert_eq!(crate::part1(&data).unwrap(), expected);
+        assert_eq!(crate::part2(&data).unwrap(), expected);
+        assert!(u128::try_from(&expected).is_err());
+    }
+
+    #[test]
+    fn implementations_agree() {
+        use crate::*;
+
+        let mut rng = Rng::new(3);
+        let mut inputs = vec![include_str!("data_small.txt").to_string()];
+        inputs.extend((0..50).map(|_| generate_input(&mut rng)));
+        inputs.extend(["", "01\n0\n1", "0\n0", "01\n1x", "1\n\n1"].map(String::from));
+        inputs.extend(["01\n1é", "0é1\n011", "1x\n01"].map(String::from));
+
+        for input in &inputs {
+            assert!(part1_implementations().check(input).is_ok(), "{input}");
+            assert!(part2_implementations().check(input).is_ok(), "{input}");
+        }
+    }
+
+    #[test]
+    fn power_report() {
+        use crate::*;
+
+        let data = include_str!("data_small.txt");
+        let power = Columns::parse(data).unwrap().power();
+        assert_eq!(power, crate::power(&parse_report(data).unwrap()));
+        assert_eq!(power.gamma, 22u32.into());
+        assert_eq!(power.epsilon, 9u32.into());
+        assert_eq!(power.consumption(), 198u32.into());
+    }
+
+    #[test]
+    fn rating_policies() {
+        use crate::{rating::Round, *};
+
+        let data = include_str!("data_small.txt");
+        let columns = Columns::parse(data).unwrap();
+        let report = parse_report(data).unwrap();
+
+        for policy in ["most:1", "most:0", "least:1", "least:0"] {
+            let policy = policy.parse::<RatingPolicy>().unwrap();
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+            assert_eq!(
+                columns.rating(policy),
+                find_rating(report.rows.iter().collect(), policy)
+            );
+            assert_eq!(
+                columns.rating(policy),
+                SortedRows::new(parse_report(data).unwrap()).rating(policy)
+            );
+        }
+
+        // the survivors of the last round are tied 1:1, so the 1 is kept
+        let oxygen = columns.rating(RatingPolicy::OXYGEN);
+        assert_eq!(oxygen.value, 23u32.into());
+        assert_eq!(
+            oxygen.rounds[..3],
+            [
+                Round {
+                    column: 0,
+                    zeros: 5,
+                    ones: 7,
+                    kept: true,
+                    survivors: 7
+                },
+                Round {
+                    column: 1,
+                    zeros: 4,
+                    ones: 3,
+                    kept: false,
+                    survivors: 4
+                },
+                Round {
+                    column: 2,
+                    zeros: 1,
+                    ones: 3,
+                    kept: true,
+                    survivors: 3
+                },
+            ]
+        );
+        assert_eq!(
+            oxygen.rounds.last(),
+            Some(&Round {
+                column: 4,
+                zeros: 1,
+                ones: 1,
+                kept: true,
+                survivors: 1
+            })
+        );
+        assert!("most".parse::<RatingPolicy>().is_err());
+    }
+
+    #[test]
+    fn inconsistent_rows() {
+        assert_eq!(
+            crate::part1("0101\n110\n1111").err(),
+            Some("Row 2 has 3 bits, expected 4 like row 1".to_string())
+        );
+        assert!(crate::part2("").is_err());
+        assert_eq!(
+            crate::part1("0101\n1é1"),
+            Err("Failed to parse measurement 1é1: Invalid digit 'é' at index 1".to_string())
+        );
     }
 }
@@ -0,0 +1,118 @@
+use std::{fmt, fmt::Write, str::FromStr};
+
+use num_bigint::BigUint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    MostCommon,
+    LeastCommon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tie {
+    PreferOne,
+    PreferZero,
+}
+
+/// Which bit survives a round of the rating filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RatingPolicy {
+    pub keep: Keep,
+    pub tie: Tie,
+}
+
+impl RatingPolicy {
+    pub const OXYGEN: RatingPolicy = RatingPolicy {
+        keep: Keep::MostCommon,
+        tie: Tie::PreferOne,
+    };
+    pub const CO2: RatingPolicy = RatingPolicy {
+        keep: Keep::LeastCommon,
+        tie: Tie::PreferZero,
+    };
+
+    /// When every survivor has the same bit that bit is kept, whatever the
+    /// policy, so the filter never runs out of rows
+    pub fn bit_to_keep(&self, zeros: usize, ones: usize) -> bool {
+        if zeros == 0 || ones == 0 {
+            return ones > 0;
+        }
+
+        match (zeros.cmp(&ones), self.keep) {
+            (std::cmp::Ordering::Equal, _) => self.tie == Tie::PreferOne,
+            (ordering, Keep::MostCommon) => ordering.is_lt(),
+            (ordering, Keep::LeastCommon) => ordering.is_gt(),
+        }
+    }
+}
+
+impl FromStr for RatingPolicy {
+    type Err = String;
+
+    /// `most:1`, `most:0`, `least:1` or `least:0`, the digit being the bit
+    /// kept on a tie
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid rating policy: {}", input);
+        let (keep, tie) = input.split_once(':').ok_or_else(invalid)?;
+
+        let keep = match keep {
+            "most" => Keep::MostCommon,
+            "least" => Keep::LeastCommon,
+            _ => return Err(invalid()),
+        };
+        let tie = match tie {
+            "1" => Tie::PreferOne,
+            "0" => Tie::PreferZero,
+            _ => return Err(invalid()),
+        };
+
+        Ok(RatingPolicy { keep, tie })
+    }
+}
+
+impl fmt::Display for RatingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keep = match self.keep {
+            Keep::MostCommon => "most",
+            Keep::LeastCommon => "least",
+        };
+        let tie = match self.tie {
+            Tie::PreferOne => 1,
+            Tie::PreferZero => 0,
+        };
+        write!(f, "{}:{}", keep, tie)
+    }
+}
+
+/// One round of the filter. `survivors` is the number of rows left after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub column: usize,
+    pub zeros: usize,
+    pub ones: usize,
+    pub kept: bool,
+    pub survivors: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rating {
+    pub value: BigUint,
+    pub rounds: Vec<Round>,
+}
+
+impl Rating {
+    pub fn trace(&self) -> String {
+        let mut out = String::from("  column  zeros  ones  kept  survivors\n");
+
+        for round in &self.rounds {
+            writeln!(
+                out,
+                "  {:<7} {:<6} {:<5} {:<5} {}",
+                round.column, round.zeros, round.ones, round.kept as u8, round.survivors
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
@@ -0,0 +1,66 @@
+use bitvec::prelude::*;
+use num_bigint::BigUint;
+
+use crate::{
+    bits_to_biguint,
+    rating::{Rating, RatingPolicy, Round},
+    Report,
+};
+
+/// The report's rows in lexicographic order. Rows sharing a prefix are then
+/// a contiguous range, split by the next column into its zeros followed by
+/// its ones, so each round of the filter is a binary search for that split.
+pub struct SortedRows {
+    rows: Vec<BitVec<u32, Msb0>>,
+}
+
+impl SortedRows {
+    pub fn new(report: Report) -> Self {
+        let mut rows = report.rows;
+        rows.sort_unstable();
+        SortedRows { rows }
+    }
+
+    pub fn rating(&self, policy: RatingPolicy) -> Rating {
+        let (mut start, mut end) = (0, self.rows.len());
+        let mut rounds = Vec::new();
+
+        for column in 0..self.rows[0].len() {
+            if end - start <= 1 {
+                break;
+            }
+
+            let split = start + self.rows[start..end].partition_point(|row| !row[column]);
+            let zeros = split - start;
+            let ones = end - split;
+
+            let keep_ones = policy.bit_to_keep(zeros, ones);
+            if keep_ones {
+                start = split;
+            } else {
+                end = split;
+            }
+
+            rounds.push(Round {
+                column,
+                zeros,
+                ones,
+                kept: keep_ones,
+                survivors: end - start,
+            });
+        }
+
+        // duplicates left at the end are the same value
+        Rating {
+            value: bits_to_biguint(&self.rows[start]),
+            rounds,
+        }
+    }
+
+    pub fn life_support(&self) -> BigUint {
+        let oxygen = self.rating(RatingPolicy::OXYGEN);
+        let co2 = self.rating(RatingPolicy::CO2);
+
+        oxygen.value * co2.value
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::{
+    game::{Game, WinEvent},
+    Board, Pos,
+};
+
+/// Draw index of every number, the first one for numbers drawn twice
+fn draw_order(numbers: &[u32]) -> HashMap<u32, usize> {
+    let mut order = HashMap::with_capacity(numbers.len());
+    for (draw, &number) in numbers.iter().enumerate() {
+        order.entry(number).or_insert(draw);
+    }
+    order
+}
+
+/// Same result as `game::play`, without replaying the draws. A line is
+/// complete on the latest draw of its cells and a board wins on the earliest
+/// of its lines, so every cell is looked up once.
+pub fn play(numbers: &[u32], boards: &[Board]) -> Game {
+    let order = draw_order(numbers);
+    let mut game = Game::default();
+
+    for (idx, board) in boards.iter().enumerate() {
+        let cell = |pos: &Pos| pos.0 * board.width + pos.1;
+
+        // usize::MAX for cells that are never drawn
+        let mut turns = vec![usize::MAX; board.cache.len()];
+        for (number, pos) in &board.cache {
+            if let Some(&draw) = order.get(number) {
+                turns[cell(pos)] = draw;
+            }
+        }
+
+        // min_by_key keeps the first of equal lines, like check_neighbors
+        let winner = board
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|pos| turns[cell(pos)]).max())
+            .enumerate()
+            .filter_map(|(line, draw)| Some((line, draw?)))
+            .min_by_key(|&(_, draw)| draw);
+
+        let (line, draw) = match winner {
+            Some((line, draw)) if draw != usize::MAX => (line, draw),
+            _ => {
+                game.never_won.push(idx);
+                continue;
+            }
+        };
+
+        let unmarked = board
+            .cache
+            .iter()
+            .filter(|(_, pos)| turns[cell(pos)] > draw)
+            .map(|(number, _)| number)
+            .sum::<u32>();
+
+        game.wins.push(WinEvent {
+            board: idx,
+            draw,
+            number: numbers[draw],
+            line: board.line(line).to_vec(),
+            score: numbers[draw] * unmarked,
+        });
+    }
+
+    // stable, so boards winning on the same draw stay in board order
+    game.wins.sort_by_key(|win| win.draw);
+    game
+}
@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use crate::{Board, Pos};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinEvent {
+    pub board: usize,
+    /// Index of the winning number in the draw order
+    pub draw: usize,
+    pub number: u32,
+    /// Cells of the first line completed by the number
+    pub line: Vec<Pos>,
+    /// The winning number times the sum of the unmarked numbers
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Game {
+    /// Boards in the order they win, boards drawn to win on the same number
+    /// are in board order
+    pub wins: Vec<WinEvent>,
+    pub never_won: Vec<usize>,
+}
+
+/// Draws `numbers` until every board has won or the numbers run out
+pub fn play(numbers: &[u32], boards: &mut [Board]) -> Game {
+    let mut won = vec![false; boards.len()];
+    let mut game = Game::default();
+
+    for (draw, &number) in numbers.iter().enumerate() {
+        for (idx, board) in boards.iter_mut().enumerate() {
+            let line = match board.mark(number) {
+                Some(line) if !won[idx] => line,
+                _ => continue,
+            };
+
+            won[idx] = true;
+            game.wins.push(WinEvent {
+                board: idx,
+                draw,
+                number,
+                line: board.line(line).to_vec(),
+                score: number * board.calc_score(),
+            });
+        }
+
+        if game.wins.len() == boards.len() {
+            break;
+        }
+    }
+
+    game.never_won = (0..boards.len()).filter(|&idx| !won[idx]).collect();
+    game
+}
+
+impl Game {
+    pub fn timeline(&self) -> String {
+        let mut out = String::from("draw  number  board  score    line\n");
+
+        for win in &self.wins {
+            let line = win
+                .line
+                .iter()
+                .map(|pos| format!("({},{})", pos.0, pos.1))
+                .collect::<Vec<_>>();
+
+            writeln!(
+                out,
+                "{:<5} {:<7} {:<6} {:<8} {}",
+                win.draw,
+                win.number,
+                win.board,
+                win.score,
+                line.join(" ")
+            )
+            .unwrap();
+        }
+
+        let never_won = self
+            .never_won
+            .iter()
+            .map(|idx| idx.to_string())
+            .collect::<Vec<_>>();
+        match never_won.is_empty() {
+            true => writeln!(out, "every board wins").unwrap(),
+            false => writeln!(out, "never won: {}", never_won.join(", ")).unwrap(),
+        }
+
+        out
+    }
+}
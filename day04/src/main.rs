@@ -1,45 +1,94 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    process::ExitCode,
+    time::Duration,
+};
 
+use game::WinEvent;
+use rig::Place;
+use rules::{parse_rules, WinRule};
+use utils::{
+    registry::{self, Implementations},
+    rng::Rng,
+    runner::{Command, Runner},
+};
+
+mod engine;
+mod game;
+mod odds;
+mod rig;
+mod rules;
+
+/// Row and column of a cell
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 struct Pos(usize, usize);
 
-const GRID_SIZE: usize = 5;
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Board {
+    width: usize,
     cache: HashMap<u32, Pos>,
     matched: HashSet<Pos>,
+    /// Lines of cells the win rules are made of
+    lines: Vec<Vec<Pos>>,
+    /// Indices of the lines going through a cell
+    lines_by_pos: HashMap<Pos, Vec<usize>>,
 }
 
 impl Board {
-    fn new(data: Vec<Vec<u32>>) -> Self {
+    /// `data` has to be a non-empty grid, parse_boards checks the rows
+    fn new(data: Vec<Vec<u32>>, rules: &[WinRule]) -> Self {
         let mut cache: HashMap<u32, Pos> = HashMap::new();
 
-        for y in 0..GRID_SIZE {
-            for (x, row) in data.iter().enumerate() {
-                let entry = row[y];
+        for (x, row) in data.iter().enumerate() {
+            for (y, &entry) in row.iter().enumerate() {
                 cache.insert(entry, Pos(x, y));
             }
         }
 
+        let lines = rules
+            .iter()
+            .flat_map(|rule| rule.lines(data[0].len(), data.len()))
+            .collect::<Vec<_>>();
+
+        let mut lines_by_pos: HashMap<Pos, Vec<usize>> = HashMap::new();
+        for (idx, line) in lines.iter().enumerate() {
+            for &pos in line {
+                lines_by_pos.entry(pos).or_default().push(idx);
+            }
+        }
+
         Board {
+            width: data[0].len(),
             cache,
             matched: HashSet::new(),
+            lines,
+            lines_by_pos,
         }
     }
 
-    fn check_neighbors(&self, position: Pos) -> bool {
-        let vertical_match = (0..GRID_SIZE).all(|i| self.matched.contains(&Pos(position.0, i)));
-        let horizontal_match = (0..GRID_SIZE).all(|i| self.matched.contains(&Pos(i, position.1)));
+    /// First complete line through `position`
+    fn check_neighbors(&self, position: Pos) -> Option<usize> {
+        self.lines_by_pos
+            .get(&position)?
+            .iter()
+            .copied()
+            .find(|&line| {
+                self.lines[line]
+                    .iter()
+                    .all(|pos| self.matched.contains(pos))
+            })
+    }
 
-        vertical_match || horizontal_match
+    fn line(&self, idx: usize) -> &[Pos] {
+        &self.lines[idx]
     }
 
-    fn mark(&mut self, value: u32) -> bool {
+    /// Marks `value` and returns the index of a line it completes
+    fn mark(&mut self, value: u32) -> Option<usize> {
         let position = match self.cache.get(&value) {
             Some(pos) => pos,
             None => {
-                return false;
+                return None;
             }
         };
 
@@ -47,6 +96,11 @@ impl Board {
         self.check_neighbors(*position)
     }
 
+    /// Unmarks every cell, to play the board again
+    fn reset(&mut self) {
+        self.matched.clear();
+    }
+
     fn calc_score(&self) -> u32 {
         self.cache
             .iter()
@@ -61,96 +115,610 @@ impl Board {
     }
 }
 
-fn parse_boards(board_data: Vec<&str>) -> Vec<Board> {
-    board_data
-        .split(|line| line.is_empty())
-        .map(|chunk| {
-            chunk
-                .iter()
-                .map(|row| {
-                    row.split_whitespace()
-                        .map(|value| value.parse::<u32>().unwrap())
-                        .collect::<Vec<u32>>()
+fn parse_board(chunk: &[&str]) -> Result<Vec<Vec<u32>>, String> {
+    let mut seen = HashSet::new();
+
+    chunk
+        .iter()
+        .map(|row| {
+            row.split_whitespace()
+                .map(|value| match value.parse::<u32>() {
+                    Ok(number) if !seen.insert(number) => {
+                        Err(format!("number {} appears twice", number))
+                    }
+                    Ok(number) => Ok(number),
+                    Err(_) => Err(format!("invalid number {:?}", value)),
                 })
-                .collect::<Vec<Vec<u32>>>()
+                .collect::<Result<Vec<u32>, String>>()
         })
-        .map(Board::new)
         .collect()
 }
 
-fn parse_data(data: &str) -> (Vec<u32>, Vec<Board>) {
+/// Boards are separated by blank lines. The size of the first board is the
+/// size of all of them.
+fn parse_boards(board_data: Vec<&str>, rules: &[WinRule]) -> Result<Vec<Board>, String> {
+    let mut boards = Vec::new();
+    let mut size = None;
+
+    let chunks = board_data
+        .split(|line| line.trim().is_empty())
+        .filter(|chunk| !chunk.is_empty());
+
+    for (idx, chunk) in chunks.enumerate() {
+        let error = |message: String| format!("Board {}: {}", idx + 1, message);
+        let rows = parse_board(chunk).map_err(error)?;
+        let (width, height) = *size.get_or_insert((rows[0].len(), rows.len()));
+
+        if width == 0 {
+            return Err(error("board is empty".to_string()));
+        }
+        if rows.len() != height {
+            return Err(error(format!("{} rows, expected {}", rows.len(), height)));
+        }
+        if let Some((row, numbers)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(error(format!(
+                "row {} has {} numbers, expected {}",
+                row + 1,
+                numbers.len(),
+                width
+            )));
+        }
+
+        boards.push(Board::new(rows, rules));
+    }
+
+    Ok(boards)
+}
+
+fn parse_data(data: &str, rules: &[WinRule]) -> Result<(Vec<u32>, Vec<Board>), String> {
     let mut lines = data.lines();
 
     let numbers = lines
         .next()
-        .unwrap()
+        .ok_or("Missing drawn numbers")?
         .split_terminator(',')
-        .map(|n| n.parse::<u32>())
+        .map(|n| n.trim().parse::<u32>())
         .collect::<Result<Vec<u32>, _>>()
-        .unwrap();
+        .map_err(|err| format!("Invalid drawn number: {}", err))?;
+
+    Ok((numbers, parse_boards(lines.collect(), rules)?))
+}
+
+/// `boards` boards of random sizes shared by the whole deck, numbers come
+/// from a pool a bit larger than a board so that some are never drawn
+fn generate_input(rng: &mut Rng, boards: u64) -> String {
+    let width = rng.gen_range(1..7) as usize;
+    let height = rng.gen_range(1..7) as usize;
+    let mut pool = (0..(width * height) as u32 + rng.gen_range(0..40) as u32).collect::<Vec<_>>();
 
-    lines.next();
+    rng.shuffle(&mut pool);
+    let drawn = rng.gen_range(0..pool.len() as u64 + 1) as usize;
+    let numbers = pool[..drawn]
+        .iter()
+        .map(|number| number.to_string())
+        .collect::<Vec<_>>();
 
-    (numbers, parse_boards(lines.collect()))
+    let mut out = numbers.join(",");
+    for _ in 0..boards {
+        rng.shuffle(&mut pool);
+        out.push('\n');
+        for row in pool[..width * height].chunks(width) {
+            let row = row.iter().map(|n| format!("{:>2}", n)).collect::<Vec<_>>();
+            out.push('\n');
+            out.push_str(&row.join(" "));
+        }
+    }
+    out
 }
 
-fn part1(data: &str) -> u32 {
-    let (numbers, mut boards) = parse_data(data);
-    let mut result = 0;
+/// The precomputed engine is the fast path, the simulation is kept to check it
+fn part1_implementations() -> Implementations<str, Result<u32, String>> {
+    Implementations::new("result#1")
+        .register("engine", |data| part1(data, &WinRule::STANDARD))
+        .register("simulation", |data| {
+            let (numbers, mut boards) = parse_data(data, &WinRule::STANDARD)?;
+            let game = game::play(&numbers, &mut boards);
+            Ok(game.wins.first().map_or(0, |win| win.score))
+        })
+}
+
+fn part2_implementations() -> Implementations<str, Result<u32, String>> {
+    Implementations::new("result#2")
+        .register("engine", |data| part2(data, &WinRule::STANDARD))
+        .register("simulation", |data| {
+            let (numbers, mut boards) = parse_data(data, &WinRule::STANDARD)?;
+            let game = game::play(&numbers, &mut boards);
+            Ok(game.wins.last().map_or(0, |win| win.score))
+        })
+}
 
-    'outer: for number in numbers {
-        for board in boards.iter_mut() {
-            if board.mark(number) {
-                result = number * board.calc_score();
-                break 'outer;
+/// Score of the first board to win, 0 if none does
+fn part1(data: &str, rules: &[WinRule]) -> Result<u32, String> {
+    let (numbers, boards) = parse_data(data, rules)?;
+    let game = engine::play(&numbers, &boards);
+
+    Ok(game.wins.first().map_or(0, |win| win.score))
+}
+
+/// Score of the last board to win, 0 if none does
+fn part2(data: &str, rules: &[WinRule]) -> Result<u32, String> {
+    let (numbers, boards) = parse_data(data, rules)?;
+    let game = engine::play(&numbers, &boards);
+
+    Ok(game.wins.last().map_or(0, |win| win.score))
+}
+
+struct Options {
+    rules: Vec<WinRule>,
+    input: Option<String>,
+    trials: usize,
+    seed: u64,
+    threads: usize,
+    board: Option<usize>,
+    place: Place,
+    number: Option<u32>,
+}
+
+/// `[--rules <rule>,...] [--input <file>] [--trials <n>] [--seed <n>]
+/// [--threads <n>] [--board <n>] [--last] [--number <n>]`, rules being `rows`, `columns`, `diagonals`, `corners`,
+/// `blackout` or `mask:<pattern>` (e.g. `mask:X.X/.X./X.X`)
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        rules: WinRule::STANDARD.to_vec(),
+        input: None,
+        trials: 1000,
+        seed: 0,
+        threads: 1,
+        board: None,
+        place: Place::First,
+        number: None,
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        let invalid = |value: &str| format!("Invalid value for {}: {}", arg, value);
+
+        match arg.as_str() {
+            "--rules" => options.rules = parse_rules(value()?)?,
+            "--input" => options.input = Some(Runner::read_input(value()?)?),
+            "--trials" => {
+                let value = value()?;
+                options.trials = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--seed" => {
+                let value = value()?;
+                options.seed = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--threads" => {
+                let value = value()?;
+                options.threads = match value.parse() {
+                    Ok(threads) if threads > 0 => threads,
+                    _ => return Err(invalid(value)),
+                };
+            }
+            "--board" => {
+                let value = value()?;
+                options.board = Some(value.parse().map_err(|_| invalid(value))?);
+            }
+            "--last" => options.place = Place::Last,
+            "--number" => {
+                let value = value()?;
+                options.number = Some(value.parse().map_err(|_| invalid(value))?);
             }
+            _ => return Err(format!("Unknown argument: {}", arg)),
         }
     }
 
-    result
+    Ok(options)
 }
 
-fn part2(data: &str) -> u32 {
-    let (numbers, mut boards) = parse_data(data);
-    let mut won: HashSet<usize> = HashSet::new();
-    let mut result = 0;
+/// The drawn numbers of an input, each of them once
+fn number_pool(numbers: Vec<u32>) -> Vec<u32> {
+    let mut seen = HashSet::new();
+    numbers
+        .into_iter()
+        .filter(|&number| seen.insert(number))
+        .collect()
+}
 
-    let boards_count = boards.len();
-    'outer: for number in numbers {
-        for (i, board) in boards.iter_mut().enumerate() {
-            if board.mark(number) && !won.contains(&i) {
-                won.insert(i);
-                result = number * board.calc_score();
+/// `data` with its drawn numbers replaced by `order`
+fn with_draws(data: &str, order: &[u32]) -> String {
+    let draws = order.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let boards = data.lines().skip(1).collect::<Vec<_>>();
 
-                if won.len() == boards_count {
-                    break 'outer;
-                }
-            }
+    format!("{}\n{}\n", draws.join(","), boards.join("\n"))
+}
+
+/// Replays a rigged input through part 1 or 2 and returns the winning event
+/// when `board` is the only board winning on it, and on `number` if given
+fn check_rigged(
+    data: &str,
+    rules: &[WinRule],
+    board: usize,
+    place: Place,
+    number: Option<u32>,
+) -> Result<WinEvent, String> {
+    let (numbers, boards) = parse_data(data, rules)?;
+    let game = engine::play(&numbers, &boards);
+    let (win, score) = match place {
+        Place::First => (game.wins.first(), part1(data, rules)?),
+        Place::Last => (game.wins.last(), part2(data, rules)?),
+    };
+
+    match win {
+        Some(win)
+            if win.board == board
+                && win.score == score
+                && number.is_none_or(|number| number == win.number)
+                && game
+                    .wins
+                    .iter()
+                    .filter(|other| other.draw == win.draw)
+                    .count()
+                    == 1 =>
+        {
+            Ok(win.clone())
         }
+        _ => Err(format!(
+            "Rigged draws don't replay with board {} winning {}",
+            board, place
+        )),
     }
+}
+
+fn day_command(data: &str, name: &str, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args)?;
+    let input = options.input.as_deref().unwrap_or(data);
 
-    result
+    match name {
+        "play" => {
+            let rules = options
+                .rules
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect::<Vec<_>>();
+
+            let (numbers, boards) = parse_data(input, &options.rules)?;
+            let game = engine::play(&numbers, &boards);
+
+            Ok(format!("rules: {}\n{}", rules.join(","), game.timeline()))
+        }
+        "odds" => {
+            let (numbers, boards) = parse_data(input, &options.rules)?;
+
+            // the drawn numbers of the input are the pool, in any order
+            let pool = number_pool(numbers);
+
+            let odds = odds::estimate(
+                &pool,
+                &boards,
+                options.trials,
+                options.seed,
+                options.threads,
+            );
+            Ok(format!(
+                "trials: {}, pool: {} numbers\n{}",
+                options.trials,
+                pool.len(),
+                odds.report()
+            ))
+        }
+        "rig" => {
+            let board = options.board.ok_or("Missing --board")?;
+            let (numbers, boards) = parse_data(input, &options.rules)?;
+
+            let order = rig::rig(
+                &number_pool(numbers),
+                &boards,
+                board,
+                options.place,
+                options.number,
+            )?;
+            let rigged = with_draws(input, &order);
+            let win = check_rigged(
+                &rigged,
+                &options.rules,
+                board,
+                options.place,
+                options.number,
+            )?;
+
+            Ok(format!(
+                "board {} wins {} on {} with a score of {}\n{}\n",
+                board,
+                options.place,
+                win.number,
+                win.score,
+                rigged.lines().next().unwrap_or_default()
+            ))
+        }
+        _ => Err(format!("Unknown command: {}", name)),
+    }
 }
 
-fn main() {
-    let data = include_str!("data.txt");
-    let result = part1(data);
-    println!("result#1: {}", result);
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
-    let result = part2(data);
-    println!("result#2: {}", result);
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match runner.command() {
+        Command::Day { name, args } => runner.print_day_output(day_command(data, &name, &args)),
+        Command::Diff { samples, seed } => {
+            let mut rng = Rng::new(seed);
+            let mut inputs = vec![data.to_string()];
+            inputs.extend((0..samples).map(|_| generate_input(&mut rng, 2000)));
+
+            let parts = [&part1_implementations(), &part2_implementations()];
+            if registry::diff::<str, _, _>(&parts, &inputs) > 0 {
+                runner.mark_failed();
+            }
+        }
+        _ => {
+            runner.run_fallible("result#1", move |_| part1(data, &WinRule::STANDARD));
+            runner.run_fallible("result#2", move |_| part2(data, &WinRule::STANDARD));
+        }
+    }
+
+    runner.finish()
 }
 
 #[cfg(test)]
 mod test {
+    use crate::{
+        rules::WinRule::{self, *},
+        Pos,
+    };
+
+    const STANDARD: [WinRule; 2] = WinRule::STANDARD;
+
     #[test]
     fn part1() {
-        let result = crate::part1(include_str!("data_small.txt"));
+        let result = crate::part1(include_str!("data_small.txt"), &STANDARD).unwrap();
         assert_eq!(result, 4512);
     }
     #[test]
     fn part2() {
-        let result = crate::part2(include_str!("data_small.txt"));
+        let result = crate::part2(include_str!("data_small.txt"), &STANDARD).unwrap();
         assert_eq!(result, 1924);
     }
+
+    #[test]
+    fn rectangular_boards() {
+        // 3 rows of 2, the first column of board 2 is complete on 3, not on 7
+        let data = "6,7,3\n\n1 2\n3 4\n5 6\n\n6 10\n7 11\n3 12\n";
+        assert_eq!(crate::part1(data, &STANDARD), Ok(3 * 33));
+        assert_eq!(crate::part2(data, &STANDARD), Ok(3 * 33));
+
+        let data = "1,2\n\n1 2 3\n4 5 6\n\n1 2\n3 4\n5 6\n";
+        assert_eq!(
+            crate::parse_data(data, &STANDARD).err(),
+            Some("Board 2: 3 rows, expected 2".to_string())
+        );
+        let data = "1,2\n\n1 2\n4 5\n\n3 7\n7 6\n";
+        assert_eq!(
+            crate::parse_data(data, &STANDARD).err(),
+            Some("Board 2: number 7 appears twice".to_string())
+        );
+    }
+
+    #[test]
+    fn win_rules() {
+        let data = include_str!("data_small.txt");
+
+        assert_eq!(crate::part1(data, &[Diagonals]), Ok(494));
+        assert_eq!(crate::part2(data, &[Diagonals]), Ok(76));
+        assert_eq!(crate::part1(data, &[Corners]), Ok(3262));
+        assert_eq!(crate::part1(data, &[Rows, Columns, Diagonals]), Ok(494));
+
+        assert_eq!(
+            Corners.lines(5, 5),
+            vec![vec![Pos(0, 0), Pos(0, 4), Pos(4, 0), Pos(4, 4)]]
+        );
+        assert_eq!(Corners.lines(1, 2), vec![vec![Pos(0, 0), Pos(1, 0)]]);
+        assert_eq!(Blackout.lines(3, 2)[0].len(), 6);
+        assert_eq!(
+            Diagonals.lines(3, 2),
+            vec![vec![Pos(0, 0), Pos(1, 1)], vec![Pos(0, 2), Pos(1, 1)]]
+        );
+
+        let mask = WinRule::mask("XX/XX").unwrap();
+        assert_eq!(mask.lines(3, 3).len(), 4);
+        assert_eq!(
+            mask.lines(3, 3)[3],
+            vec![Pos(1, 1), Pos(1, 2), Pos(2, 1), Pos(2, 2)]
+        );
+        assert_eq!(mask.to_string().parse(), Ok(mask));
+        assert!(WinRule::mask("X./X").is_err());
+        assert!(crate::rules::parse_rules("rows,stars").is_err());
+    }
+
+    #[test]
+    fn game_timeline() {
+        use crate::{game::play, parse_data, WinEvent};
+
+        let data = include_str!("data_small.txt");
+        let (numbers, mut boards) = parse_data(data, &STANDARD).unwrap();
+        let game = play(&numbers, &mut boards);
+
+        assert_eq!(
+            game.wins[0],
+            WinEvent {
+                board: 2,
+                draw: 11,
+                number: 24,
+                line: (0..5).map(|column| Pos(0, column)).collect(),
+                score: 4512
+            }
+        );
+        assert_eq!(
+            game.wins
+                .iter()
+                .map(|win| (win.board, win.number))
+                .collect::<Vec<_>>(),
+            vec![(2, 24), (0, 16), (1, 13)]
+        );
+        assert!(game.never_won.is_empty());
+
+        // only the third board has all its corners drawn before 3
+        let (_, mut boards) = parse_data(data, &[Corners]).unwrap();
+        let game = play(&numbers[..numbers.len() - 3], &mut boards);
+        assert_eq!(game.wins.len(), 1);
+        assert_eq!(game.never_won, vec![0, 1]);
+    }
+
+    #[test]
+    fn inconsistent_boards() {
+        let data = "1,2\n\n1 2 3\n4 5 6\n\n7 8 9\n10 11\n";
+        assert_eq!(
+            crate::parse_data(data, &STANDARD).err(),
+            Some("Board 2: row 2 has 2 numbers, expected 3".to_string())
+        );
+
+        let data = "1,2\n\n1 2 3\n4 5 6\n\n7 8 9\n";
+        assert_eq!(
+            crate::parse_data(data, &STANDARD).err(),
+            Some("Board 2: 1 rows, expected 2".to_string())
+        );
+
+        let data = "1,2\n\n1 2 x\n";
+        assert_eq!(
+            crate::parse_data(data, &STANDARD).err(),
+            Some("Board 1: invalid number \"x\"".to_string())
+        );
+    }
+
+    #[test]
+    fn engine_matches_simulation() {
+        use crate::{engine, game, generate_input, parse_data, rules::parse_rules};
+        use utils::rng::Rng;
+
+        let mut rng = Rng::new(4);
+        let rule_sets = [
+            "rows,columns",
+            "diagonals",
+            "corners,blackout",
+            "mask:X.X/.X.",
+        ];
+
+        for rules in rule_sets {
+            let rules = parse_rules(rules).unwrap();
+            for _ in 0..5 {
+                let input = generate_input(&mut rng, 2000);
+                let (numbers, mut boards) = parse_data(&input, &rules).unwrap();
+
+                assert_eq!(
+                    engine::play(&numbers, &boards),
+                    game::play(&numbers, &mut boards)
+                );
+            }
+        }
+
+        // a number drawn twice only counts the first time
+        let data = "1,2,1,3,4\n\n1 2\n3 4\n";
+        let (numbers, mut boards) = parse_data(data, &STANDARD).unwrap();
+        let game = engine::play(&numbers, &boards);
+        assert_eq!(game, game::play(&numbers, &mut boards));
+        assert_eq!((game.wins[0].draw, game.wins[0].score), (1, 2 * 7));
+    }
+
+    #[test]
+    fn implementations_agree() {
+        use crate::*;
+
+        let data = include_str!("data.txt");
+        assert_eq!(part1_implementations().check(data), Ok(Ok(33462)));
+        assert_eq!(part2_implementations().check(data), Ok(Ok(30070)));
+        assert!(part1_implementations().check("1,x").is_ok());
+    }
+
+    #[test]
+    fn win_odds() {
+        use crate::{odds, parse_data};
+
+        // the first two boards win on whichever of 1 and 2 comes first, the
+        // third never does because 99 isn't in the pool
+        let (numbers, boards) = parse_data("1,2\n\n1\n\n2\n\n99\n", &STANDARD).unwrap();
+        let result = odds::estimate(&numbers, &boards, 2000, 7, 1);
+
+        assert_eq!(result.first[2] + result.last[2], 0);
+        assert_eq!(result.first[0] + result.first[1], 2000);
+        assert_eq!(result.last[0] + result.last[1], 2000);
+        let (low, high) = odds::wilson(result.first[0], result.trials);
+        assert!(low < 0.5 && 0.5 < high, "{low} {high}");
+
+        // same trials whatever the number of threads
+        assert_eq!(odds::estimate(&numbers, &boards, 2000, 7, 3), result);
+        assert_eq!(odds::estimate(&numbers, &boards, 2, 7, 8).trials, 2);
+    }
+
+    #[test]
+    fn wilson_interval() {
+        use crate::odds::wilson;
+
+        let (low, high) = wilson(0, 100);
+        assert_eq!(low, 0.0);
+        assert!(high > 0.0 && high < 0.05);
+
+        let (low, high) = wilson(100, 100);
+        assert!(low > 0.95 && (high - 1.0).abs() < 1e-9);
+
+        let (low, high) = wilson(50, 100);
+        assert!((0.40..0.41).contains(&low) && (0.59..0.60).contains(&high));
+        assert_eq!(wilson(0, 0), (0.0, 1.0));
+    }
+
+    #[test]
+    fn rigged_draws() {
+        use crate::{check_rigged, number_pool, parse_data, rig::rig, with_draws, Place::*};
+
+        let data = include_str!("data_small.txt");
+        let (numbers, boards) = parse_data(data, &STANDARD).unwrap();
+        let pool = number_pool(numbers);
+
+        for board in 0..boards.len() {
+            for place in [First, Last] {
+                let order = rig(&pool, &boards, board, place, None).unwrap();
+                let rigged = with_draws(data, &order);
+                assert!(check_rigged(&rigged, &STANDARD, board, place, None).is_ok());
+            }
+        }
+
+        // 14 is on every board, in the middle of the first one's third row
+        let order = rig(&pool, &boards, 0, Last, Some(14)).unwrap();
+        let win = check_rigged(&with_draws(data, &order), &STANDARD, 0, Last, Some(14));
+        assert_eq!(win.map(|win| win.number), Ok(14));
+        assert_eq!(
+            rig(&pool, &boards, 0, First, Some(99)),
+            Err("Board 0 can't win first on 99".to_string())
+        );
+    }
+
+    #[test]
+    fn impossible_rigs() {
+        use crate::{parse_data, rig::rig, Place::*};
+
+        // the second board is the first one transposed, they share every line
+        let data = "1,2,3,4,5\n\n1 2\n3 4\n\n1 3\n2 4\n\n5 1\n6 7\n";
+        let (numbers, boards) = parse_data(data, &STANDARD).unwrap();
+
+        assert_eq!(
+            rig(&numbers, &boards, 0, First, None),
+            Err("Board 0 can't win first".to_string())
+        );
+        assert_eq!(
+            rig(&numbers, &boards, 1, Last, None),
+            Err("Board 1 can't win last".to_string())
+        );
+        assert_eq!(
+            rig(&numbers, &boards, 2, First, None),
+            Ok(vec![1, 5, 2, 3, 4])
+        );
+        assert!(rig(&numbers, &boards, 3, First, None).is_err());
+    }
 }
@@ -0,0 +1,131 @@
+use std::{fmt::Write, ops::Range, thread};
+
+use utils::rng::Rng;
+
+use crate::{game::play, Board};
+
+/// How often each board won first and last over `trials` random draw orders.
+/// Boards winning on the same number share the place, so the counts of a
+/// trial can add up to more than one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Odds {
+    pub trials: usize,
+    pub first: Vec<usize>,
+    pub last: Vec<usize>,
+}
+
+/// Plays `trials` shuffles of `pool` split over `threads` threads. Every
+/// trial has its own generator, so the estimate only depends on `seed`.
+pub fn estimate(pool: &[u32], boards: &[Board], trials: usize, seed: u64, threads: usize) -> Odds {
+    let threads = threads.clamp(1, trials.max(1));
+    let chunk = trials.div_ceil(threads);
+
+    let tallies = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|idx| {
+                let range = idx * chunk..((idx + 1) * chunk).min(trials);
+                scope.spawn(move || tally(pool, boards, range, seed))
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("Trial thread panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut odds = Odds {
+        trials,
+        first: vec![0; boards.len()],
+        last: vec![0; boards.len()],
+    };
+    for tally in tallies {
+        for (idx, (first, last)) in tally.first.into_iter().zip(tally.last).enumerate() {
+            odds.first[idx] += first;
+            odds.last[idx] += last;
+        }
+    }
+    odds
+}
+
+fn tally(pool: &[u32], boards: &[Board], trials: Range<usize>, seed: u64) -> Odds {
+    let mut boards = boards.to_vec();
+    let mut order = pool.to_vec();
+    let mut odds = Odds {
+        trials: trials.len(),
+        first: vec![0; boards.len()],
+        last: vec![0; boards.len()],
+    };
+
+    for trial in trials {
+        let mut rng = Rng::new(seed.wrapping_add(trial as u64));
+        order.copy_from_slice(pool);
+        rng.shuffle(&mut order);
+        boards.iter_mut().for_each(Board::reset);
+
+        let game = play(&order, &mut boards);
+        let (first, last) = match (game.wins.first(), game.wins.last()) {
+            (Some(first), Some(last)) => (first.draw, last.draw),
+            _ => continue,
+        };
+
+        for win in &game.wins {
+            if win.draw == first {
+                odds.first[win.board] += 1;
+            }
+            if win.draw == last {
+                odds.last[win.board] += 1;
+            }
+        }
+    }
+
+    odds
+}
+
+/// 95% Wilson score interval of a proportion. Unlike the normal
+/// approximation it stays within [0, 1] and isn't empty for 0 or `trials`.
+pub fn wilson(successes: usize, trials: usize) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let z = 1.96;
+    let n = trials as f64;
+    let p = successes as f64 / n;
+
+    let denominator = 1.0 + z * z / n;
+    let centre = (p + z * z / (2.0 * n)) / denominator;
+    let margin = z * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt() / denominator;
+
+    ((centre - margin).max(0.0), (centre + margin).min(1.0))
+}
+
+impl Odds {
+    pub fn report(&self) -> String {
+        let mut out = String::from("board  first  95% ci          last   95% ci\n");
+        let ratio = |count: usize| match self.trials {
+            0 => 0.0,
+            trials => count as f64 / trials as f64,
+        };
+
+        for (idx, (&first, &last)) in self.first.iter().zip(&self.last).enumerate() {
+            let (first_low, first_high) = wilson(first, self.trials);
+            let (last_low, last_high) = wilson(last, self.trials);
+
+            writeln!(
+                out,
+                "{:<6} {:.3}  [{:.3}, {:.3}]  {:.3}  [{:.3}, {:.3}]",
+                idx,
+                ratio(first),
+                first_low,
+                first_high,
+                ratio(last),
+                last_low,
+                last_high
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
@@ -0,0 +1,247 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::Board;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Place {
+    First,
+    Last,
+}
+
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Place::First => write!(f, "first"),
+            Place::Last => write!(f, "last"),
+        }
+    }
+}
+
+/// Nodes visited while looking for a last-place order before giving up
+const SEARCH_LIMIT: usize = 20_000;
+
+/// The numbers of every line of a board
+fn number_lines(board: &Board) -> Vec<Vec<u32>> {
+    let numbers = board
+        .cache
+        .iter()
+        .map(|(&number, &pos)| (pos, number))
+        .collect::<HashMap<_, _>>();
+
+    board
+        .lines
+        .iter()
+        .map(|line| line.iter().map(|pos| numbers[pos]).collect())
+        .collect()
+}
+
+fn has_line_in(lines: &[Vec<u32>], drawn: &HashSet<u32>) -> bool {
+    lines
+        .iter()
+        .any(|line| line.iter().all(|number| drawn.contains(number)))
+}
+
+/// A draw order taken from `pool` in which `board` is the only board winning
+/// on its number and wins first or last, on `number` if there is one
+pub fn rig(
+    pool: &[u32],
+    boards: &[Board],
+    board: usize,
+    place: Place,
+    number: Option<u32>,
+) -> Result<Vec<u32>, String> {
+    if board >= boards.len() {
+        return Err(format!("Board {} doesn't exist", board));
+    }
+
+    let lines = boards.iter().map(number_lines).collect::<Vec<_>>();
+    let in_pool = pool.iter().copied().collect::<HashSet<_>>();
+
+    // (winning number, line it completes), lines that can't be drawn skipped
+    let mut wins = Vec::new();
+    for line in &lines[board] {
+        if !line.iter().all(|number| in_pool.contains(number)) {
+            continue;
+        }
+        for &last in line {
+            if number.is_none_or(|number| number == last) {
+                wins.push((last, line));
+            }
+        }
+    }
+
+    let on = match number {
+        Some(number) => format!(" on {}", number),
+        None => String::new(),
+    };
+
+    match place {
+        Place::First => wins
+            .into_iter()
+            .find_map(|(last, line)| rig_first(pool, &lines, board, last, line))
+            .ok_or(format!("Board {} can't win first{}", board, on)),
+        Place::Last => {
+            let mut search = Search {
+                lines: &lines,
+                in_pool: &in_pool,
+                board,
+                last: 0,
+                steps: 0,
+            };
+
+            for (last, line) in wins {
+                search.last = last;
+                if let Some(order) = search.run(pool, line) {
+                    return Ok(order);
+                }
+            }
+
+            match search.steps > SEARCH_LIMIT {
+                true => Err(format!(
+                    "Gave up looking for an order where board {} wins last{} after {} steps",
+                    board, on, SEARCH_LIMIT
+                )),
+                false => Err(format!("Board {} can't win last{}", board, on)),
+            }
+        }
+    }
+}
+
+/// Drawing just the line is the best chance: every other number could only
+/// complete a line of another board
+fn rig_first(
+    pool: &[u32],
+    lines: &[Vec<Vec<u32>>],
+    board: usize,
+    last: u32,
+    line: &[u32],
+) -> Option<Vec<u32>> {
+    let mut drawn = line.iter().copied().collect::<HashSet<_>>();
+    let others_win = lines
+        .iter()
+        .enumerate()
+        .any(|(idx, lines)| idx != board && has_line_in(lines, &drawn));
+
+    drawn.remove(&last);
+    if others_win || has_line_in(&lines[board], &drawn) {
+        return None;
+    }
+
+    // the rest of the pool can't change who won first
+    let mut order = line
+        .iter()
+        .copied()
+        .filter(|&n| n != last)
+        .collect::<Vec<_>>();
+    order.push(last);
+    order.extend(pool.iter().filter(|number| !line.contains(number)));
+    Some(order)
+}
+
+/// Picks the numbers drawn before `last`. The board must not win before it,
+/// and any other board completing a line on `last` must already have won,
+/// so that the board is the last one to win. Nothing is drawn after `last`.
+struct Search<'a> {
+    lines: &'a [Vec<Vec<u32>>],
+    in_pool: &'a HashSet<u32>,
+    board: usize,
+    last: u32,
+    steps: usize,
+}
+
+impl Search<'_> {
+    fn run(&mut self, pool: &[u32], line: &[u32]) -> Option<Vec<u32>> {
+        let mut drawn = line
+            .iter()
+            .copied()
+            .filter(|&number| number != self.last)
+            .collect::<HashSet<_>>();
+        let free = pool
+            .iter()
+            .copied()
+            .filter(|number| !line.contains(number))
+            .collect::<Vec<_>>();
+
+        if !self.search(&free, &mut drawn, &mut HashSet::new()) {
+            return None;
+        }
+
+        let mut order = pool
+            .iter()
+            .copied()
+            .filter(|number| drawn.contains(number))
+            .collect::<Vec<_>>();
+        order.push(self.last);
+        Some(order)
+    }
+
+    /// Whether a line of `lines` is complete once `last` is drawn after `drawn`
+    fn wins_on_last(&self, drawn: &HashSet<u32>, lines: &[Vec<u32>]) -> bool {
+        lines.iter().any(|line| {
+            line.contains(&self.last)
+                && line
+                    .iter()
+                    .all(|number| *number == self.last || drawn.contains(number))
+        })
+    }
+
+    /// A board that would win on `last` but can't win before it anymore
+    fn beaten(&self, drawn: &HashSet<u32>, skipped: &HashSet<u32>, lines: &[Vec<u32>]) -> bool {
+        let can_win_before = lines.iter().any(|line| {
+            !line.contains(&self.last)
+                && line
+                    .iter()
+                    .all(|number| self.in_pool.contains(number) && !skipped.contains(number))
+        });
+
+        !can_win_before && self.wins_on_last(drawn, lines)
+    }
+
+    /// Draws `free[0]` or skips it, drawing first so that as many other
+    /// boards as possible win before the board
+    fn search(
+        &mut self,
+        free: &[u32],
+        drawn: &mut HashSet<u32>,
+        skipped: &mut HashSet<u32>,
+    ) -> bool {
+        self.steps += 1;
+        if self.steps > SEARCH_LIMIT || has_line_in(&self.lines[self.board], drawn) {
+            return false;
+        }
+
+        let mut others = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != self.board);
+        let (&number, rest) = match free.split_first() {
+            Some(split) => split,
+            None => {
+                return others.all(|(_, lines)| {
+                    has_line_in(lines, drawn) || !self.wins_on_last(drawn, lines)
+                })
+            }
+        };
+        if others
+            .clone()
+            .any(|(_, lines)| self.beaten(drawn, skipped, lines))
+        {
+            return false;
+        }
+
+        drawn.insert(number);
+        if self.search(rest, drawn, skipped) {
+            return true;
+        }
+        drawn.remove(&number);
+
+        skipped.insert(number);
+        let found = self.search(rest, drawn, skipped);
+        skipped.remove(&number);
+        found
+    }
+}
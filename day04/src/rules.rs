@@ -0,0 +1,165 @@
+use std::{fmt, str::FromStr};
+
+use crate::Pos;
+
+/// A way of winning a bingo board. Every rule is expanded into lines, sets of
+/// cells that win once all of them are marked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinRule {
+    Rows,
+    Columns,
+    /// Both diagonals, starting in the top corners. On rectangular boards
+    /// they stop at the shorter side.
+    Diagonals,
+    Corners,
+    /// Every cell of the board
+    Blackout,
+    /// A pattern that wins at any offset where it fits on the board
+    Mask {
+        width: usize,
+        height: usize,
+        cells: Vec<Pos>,
+    },
+}
+
+impl WinRule {
+    pub const STANDARD: [WinRule; 2] = [WinRule::Rows, WinRule::Columns];
+
+    /// Parses a mask pattern: rows separated by `/`, `X` for cells that have
+    /// to be marked and `.` for the others, e.g. `X.X/.X./X.X`
+    pub fn mask(pattern: &str) -> Result<Self, String> {
+        let rows = pattern.split('/').collect::<Vec<_>>();
+        let width = rows[0].len();
+        let mut cells = Vec::new();
+
+        for (row, line) in rows.iter().enumerate() {
+            if line.len() != width {
+                return Err(format!("Mask rows have different lengths: {}", pattern));
+            }
+
+            for (column, cell) in line.chars().enumerate() {
+                match cell {
+                    'X' => cells.push(Pos(row, column)),
+                    '.' => {}
+                    _ => return Err(format!("Invalid mask cell {:?} in {}", cell, pattern)),
+                }
+            }
+        }
+
+        if cells.is_empty() {
+            return Err(format!("Mask has no cells: {}", pattern));
+        }
+
+        Ok(WinRule::Mask {
+            width,
+            height: rows.len(),
+            cells,
+        })
+    }
+
+    pub fn lines(&self, width: usize, height: usize) -> Vec<Vec<Pos>> {
+        let cells = |row: usize, columns: std::ops::Range<usize>| {
+            columns.map(move |column| Pos(row, column))
+        };
+
+        let mut lines: Vec<Vec<Pos>> = match self {
+            WinRule::Rows => (0..height)
+                .map(|row| cells(row, 0..width).collect())
+                .collect(),
+            WinRule::Columns => (0..width)
+                .map(|column| (0..height).map(|row| Pos(row, column)).collect())
+                .collect(),
+            WinRule::Diagonals => {
+                let size = width.min(height);
+                vec![
+                    (0..size).map(|i| Pos(i, i)).collect(),
+                    (0..size).map(|i| Pos(i, width - 1 - i)).collect(),
+                ]
+            }
+            WinRule::Corners => vec![vec![
+                Pos(0, 0),
+                Pos(0, width - 1),
+                Pos(height - 1, 0),
+                Pos(height - 1, width - 1),
+            ]],
+            WinRule::Blackout => vec![(0..height).flat_map(|row| cells(row, 0..width)).collect()],
+            WinRule::Mask {
+                width: mask_width,
+                height: mask_height,
+                cells,
+            } => {
+                let offsets = (0..(height + 1).saturating_sub(*mask_height)).flat_map(|row| {
+                    (0..(width + 1).saturating_sub(*mask_width)).map(move |column| (row, column))
+                });
+
+                offsets
+                    .map(|(row, column)| {
+                        cells
+                            .iter()
+                            .map(|cell| Pos(cell.0 + row, cell.1 + column))
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+
+        // corners and diagonals overlap on narrow boards
+        for line in lines.iter_mut() {
+            line.sort_by_key(|pos| (pos.0, pos.1));
+            line.dedup();
+        }
+        lines
+    }
+}
+
+impl FromStr for WinRule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "rows" => Ok(WinRule::Rows),
+            "columns" => Ok(WinRule::Columns),
+            "diagonals" => Ok(WinRule::Diagonals),
+            "corners" => Ok(WinRule::Corners),
+            "blackout" => Ok(WinRule::Blackout),
+            _ => match input.strip_prefix("mask:") {
+                Some(pattern) => WinRule::mask(pattern),
+                None => Err(format!("Unknown win rule: {}", input)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for WinRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WinRule::Rows => write!(f, "rows"),
+            WinRule::Columns => write!(f, "columns"),
+            WinRule::Diagonals => write!(f, "diagonals"),
+            WinRule::Corners => write!(f, "corners"),
+            WinRule::Blackout => write!(f, "blackout"),
+            WinRule::Mask {
+                width,
+                height,
+                cells,
+            } => {
+                let rows = (0..*height)
+                    .map(|row| {
+                        (0..*width)
+                            .map(|column| match cells.contains(&Pos(row, column)) {
+                                true => 'X',
+                                false => '.',
+                            })
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, "mask:{}", rows.join("/"))
+            }
+        }
+    }
+}
+
+/// Comma separated rules, e.g. `rows,columns,mask:XX/XX`
+pub fn parse_rules(input: &str) -> Result<Vec<WinRule>, String> {
+    input.split(',').map(WinRule::from_str).collect()
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::{gcd, Mode, Pair};
+
+/// Direction and offset shared by every point of a line
+type Line = (i64, i64, i128);
+
+/// A segment as `from + k * step` for `k` in `0..=steps`, `step` being the
+/// smallest lattice step along its line
+struct Segment {
+    from: (i64, i64),
+    step: (i64, i64),
+    steps: i64,
+}
+
+fn cross(a: (i64, i64), b: (i64, i64)) -> i128 {
+    a.0 as i128 * b.1 as i128 - a.1 as i128 * b.0 as i128
+}
+
+fn dot(a: (i64, i64), b: (i64, i64)) -> i128 {
+    a.0 as i128 * b.0 as i128 + a.1 as i128 * b.1 as i128
+}
+
+impl Segment {
+    fn new(pair: &Pair) -> Self {
+        let mut from = (pair.0.x as i64, pair.0.y as i64);
+        let to = (pair.1.x as i64, pair.1.y as i64);
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = gcd(dx.unsigned_abs() as u32, dy.unsigned_abs() as u32) as i64;
+
+        // a single point is taken as a horizontal segment
+        if steps == 0 {
+            return Segment {
+                from,
+                step: (1, 0),
+                steps,
+            };
+        }
+
+        // one direction per line, so that collinear segments end up together
+        let mut step = (dx / steps, dy / steps);
+        if step.0 < 0 || (step.0 == 0 && step.1 < 0) {
+            step = (-step.0, -step.1);
+            from = to;
+        }
+
+        Segment { from, step, steps }
+    }
+
+    fn line(&self) -> Line {
+        (self.step.0, self.step.1, cross(self.step, self.from))
+    }
+
+    /// Distance between two lattice points of the line, in `position` units
+    fn spacing(&self) -> i128 {
+        dot(self.step, self.step)
+    }
+
+    /// The positions of the segment's ends, the second one excluded
+    fn span(&self) -> (i128, i128) {
+        let start = dot(self.from, self.step);
+        (start, start + (self.steps as i128 + 1) * self.spacing())
+    }
+
+    /// The lattice point both segments go through, if any
+    fn crossing(&self, other: &Segment) -> Option<(i64, i64)> {
+        let denominator = cross(self.step, other.step);
+        if denominator == 0 {
+            return None;
+        }
+
+        let offset = (other.from.0 - self.from.0, other.from.1 - self.from.1);
+        let s = cross(offset, other.step);
+        let t = cross(offset, self.step);
+        if s % denominator != 0 || t % denominator != 0 {
+            return None;
+        }
+
+        let (s, t) = (s / denominator, t / denominator);
+        if !(0..=self.steps as i128).contains(&s) || !(0..=other.steps as i128).contains(&t) {
+            return None;
+        }
+
+        let s = s as i64;
+        Some((self.from.0 + s * self.step.0, self.from.1 + s * self.step.1))
+    }
+}
+
+/// Parts of a line covered at least twice, as sorted `(start, end)` spans
+fn overlaps_along(segments: &[&Segment]) -> Vec<(i128, i128)> {
+    let mut events = segments
+        .iter()
+        .flat_map(|segment| {
+            let (start, end) = segment.span();
+            [(start, 1), (end, -1)]
+        })
+        .collect::<Vec<_>>();
+    events.sort_unstable();
+
+    let mut overlaps: Vec<(i128, i128)> = Vec::new();
+    let mut covering = 0;
+    let mut previous = 0;
+
+    for (position, change) in events {
+        if covering >= 2 && position > previous {
+            match overlaps.last_mut() {
+                Some(last) if last.1 == previous => last.1 = position,
+                _ => overlaps.push((previous, position)),
+            }
+        }
+        covering += change;
+        previous = position;
+    }
+
+    overlaps
+}
+
+/// Counts the points covered by at least two of the segments `mode` keeps,
+/// without going through their points. Collinear segments are merged as
+/// intervals along their line and other segments meet at most once, at a
+/// crossing that may also be on such an interval.
+///
+/// This is not a sweep line: the crossings come from checking every pair of
+/// segments, so the cost is quadratic in the number of segments. It doesn't
+/// depend on their length though, which is what matters for the puzzle's
+/// few hundred segments with coordinates in the millions.
+pub fn count_overlaps(pairs: &[Pair], mode: Mode) -> usize {
+    let segments = pairs
+        .iter()
+        .filter(|pair| mode.keeps(pair))
+        .map(Segment::new)
+        .collect::<Vec<_>>();
+
+    let mut lines: HashMap<Line, Vec<&Segment>> = HashMap::new();
+    for segment in &segments {
+        lines.entry(segment.line()).or_default().push(segment);
+    }
+
+    let overlaps = lines
+        .iter()
+        .map(|(&line, segments)| (line, overlaps_along(segments)))
+        .collect::<HashMap<_, _>>();
+
+    let collinear = lines
+        .iter()
+        .map(|(line, segments)| {
+            let spacing = segments[0].spacing();
+            overlaps[line]
+                .iter()
+                .map(|(start, end)| ((end - start) / spacing) as usize)
+                .sum::<usize>()
+        })
+        .sum::<usize>();
+
+    let overlapping = |line: &Line, point: (i64, i64)| {
+        let spans = &overlaps[line];
+        let position = dot(point, (line.0, line.1));
+        let idx = spans.partition_point(|span| span.1 <= position);
+        idx < spans.len() && spans[idx].0 <= position
+    };
+
+    // any line with an overlap through a crossing crosses the other lines
+    // there too, so each crossing knows every such line
+    let mut crossings: HashMap<(i64, i64), Vec<Line>> = HashMap::new();
+    for (idx, a) in segments.iter().enumerate() {
+        for b in &segments[idx + 1..] {
+            if let Some(point) = a.crossing(b) {
+                let lines = crossings.entry(point).or_default();
+                lines.extend([a.line(), b.line()]);
+            }
+        }
+    }
+
+    // a crossing is counted once, whether it lies on no overlap or on the
+    // overlaps of several lines
+    let mut count = collinear;
+    for (point, mut lines) in crossings {
+        lines.sort_unstable();
+        lines.dedup();
+        match lines.iter().filter(|line| overlapping(line, point)).count() {
+            0 => count += 1,
+            overlaps => count -= overlaps - 1,
+        }
+    }
+    count
+}
@@ -1,24 +1,93 @@
 use std::{
     cmp::{max, min},
-    collections::HashMap,
     error,
-    io::{Error, ErrorKind},
+    fmt::{self, Write},
+    io::Error,
+    process::ExitCode,
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
+use utils::{
+    registry::{self, Implementations},
+    rng::Rng,
+    runner::{Command, Runner},
+};
+
+mod intervals;
+mod storage;
+mod vents;
+
+use storage::{Bounds, Storage};
+use vents::VentMap;
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Point {
     x: i32,
     y: i32,
 }
 
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
 #[derive(Debug)]
 struct Pair(Point, Point);
 
+/// Which segments are rasterised, the others are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Horizontal and vertical segments
+    Axis,
+    /// Axis segments and 45° diagonals
+    Diagonal,
+    /// Segments of any slope
+    Any,
+}
+
+impl Mode {
+    fn keeps(&self, pair: &Pair) -> bool {
+        let dx = (pair.1.x as i64 - pair.0.x as i64).unsigned_abs();
+        let dy = (pair.1.y as i64 - pair.0.y as i64).unsigned_abs();
+
+        match self {
+            Mode::Axis => dx == 0 || dy == 0,
+            Mode::Diagonal => dx == 0 || dy == 0 || dx == dy,
+            Mode::Any => true,
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "axis" => Ok(Mode::Axis),
+            "diagonal" => Ok(Mode::Diagonal),
+            "any" => Ok(Mode::Any),
+            _ => Err(format!("Unknown mode: {}", input)),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Axis => write!(f, "axis"),
+            Mode::Diagonal => write!(f, "diagonal"),
+            Mode::Any => write!(f, "any"),
+        }
+    }
+}
+
 fn parse_tuple(s: &str) -> Result<Point, Box<dyn error::Error>> {
     let nums: Vec<&str> = s.split(',').collect();
 
     if nums.len() != 2 {
-        return Err(Box::new(Error::new(ErrorKind::Other, "invalid arguments")));
+        return Err(Box::new(Error::other("invalid arguments")));
     }
 
     let x = nums[0].parse::<i32>()?;
@@ -33,8 +102,7 @@ fn parse_line(s: &str) -> Result<Pair, Box<dyn error::Error>> {
     let point_b = points.next().unwrap();
 
     if points.next().is_some() {
-        return Err(Box::new(Error::new(
-            ErrorKind::Other,
+        return Err(Box::new(Error::other(
             "Line parsing failed: invalid arguments",
         )));
     }
@@ -76,7 +144,7 @@ fn line_to_points(pair: &Pair) -> Option<Vec<Point>> {
 }
 
 fn line_to_points_advanced(pair: &Pair) -> Option<Vec<Point>> {
-    let mut points = line_to_points(pair).unwrap_or(Vec::new());
+    let mut points = line_to_points(pair).unwrap_or_default();
 
     let (left_most, right_most) = if pair.0.x < pair.1.x {
         (&pair.0, &pair.1)
@@ -84,7 +152,12 @@ fn line_to_points_advanced(pair: &Pair) -> Option<Vec<Point>> {
         (&pair.1, &pair.0)
     };
 
-    if (left_most.x - right_most.x).unsigned_abs() == (left_most.y - right_most.y).unsigned_abs() {
+    // a single point is already there as a vertical line, adding it again
+    // made it overlap itself
+    if points.is_empty()
+        && (left_most.x - right_most.x).unsigned_abs()
+            == (left_most.y - right_most.y).unsigned_abs()
+    {
         for x in left_most.x..=right_most.x {
             let y = if left_most.y < right_most.y {
                 left_most.y + x - left_most.x
@@ -102,56 +175,320 @@ fn line_to_points_advanced(pair: &Pair) -> Option<Vec<Point>> {
     Some(points)
 }
 
-fn part1(data: &str) -> usize {
-    let mut board: HashMap<Point, u32> = HashMap::new();
-
-    let intersection_count =
-        parse_lines(data)
-            .iter()
-            .filter_map(line_to_points)
-            .fold(0, |mut acc, points| {
-                for point in points {
-                    let point_count = board.entry(point).or_insert(0);
-                    *point_count += 1;
-
-                    if *point_count == 2 {
-                        acc += 1;
-                    }
-                }
+fn gcd(a: u32, b: u32) -> u32 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
 
-                acc
-            });
+/// Every lattice point the segment passes through. Consecutive points are
+/// `(dx/g, dy/g)` apart, `g` being the gcd of the distances along the axes.
+fn line_to_points_any(pair: &Pair) -> Vec<Point> {
+    // in i64, a segment can span more than i32::MAX
+    let (x, y) = (pair.0.x as i64, pair.0.y as i64);
+    let dx = pair.1.x as i64 - x;
+    let dy = pair.1.y as i64 - y;
+    let steps = gcd(dx.unsigned_abs() as u32, dy.unsigned_abs() as u32) as i64;
+
+    // a segment of a single point has no direction
+    let (step_x, step_y) = match steps {
+        0 => (0, 0),
+        _ => (dx / steps, dy / steps),
+    };
 
-    intersection_count
+    // every point is between the ends, so it fits in i32 again
+    (0..=steps)
+        .map(|i| Point {
+            x: (x + i * step_x) as i32,
+            y: (y + i * step_y) as i32,
+        })
+        .collect()
 }
 
-fn part2(data: &str) -> usize {
-    let mut board: HashMap<Point, u32> = HashMap::new();
+fn rasterise(pair: &Pair, mode: Mode) -> Option<Vec<Point>> {
+    match mode {
+        Mode::Axis => line_to_points(pair),
+        Mode::Diagonal => line_to_points_advanced(pair),
+        Mode::Any => Some(line_to_points_any(pair)),
+    }
+}
 
+fn kept_pairs(data: &str, mode: Mode) -> Vec<Pair> {
     parse_lines(data)
+        .into_iter()
+        .filter(|pair| mode.keeps(pair))
+        .collect()
+}
+
+/// Points covered by at least two of the segments `mode` keeps, going
+/// through every point of every segment, and the storage used for them.
+/// `storage` is returned as is when no segment is kept.
+fn count_points(data: &str, mode: Mode, storage: Storage) -> Result<(usize, Storage), String> {
+    let pairs = kept_pairs(data, mode);
+    let bounds = match Bounds::of(&pairs) {
+        Some(bounds) => bounds,
+        None => return Ok((0, storage)),
+    };
+
+    let storage = storage.resolve(bounds)?;
+    let points = pairs
         .iter()
-        .filter_map(line_to_points_advanced)
-        .fold(0, |mut acc, points| {
-            for point in points {
-                let new_value = board.entry(point).or_insert(0);
-                *new_value += 1;
-
-                if *new_value == 2 {
-                    acc += 1;
+        .filter_map(|pair| rasterise(pair, mode))
+        .flatten();
+    Ok((
+        storage::count_at_least_twice(points, bounds, storage),
+        storage,
+    ))
+}
+
+fn count_overlaps(data: &str, mode: Mode) -> usize {
+    intervals::count_overlaps(&parse_lines(data), mode)
+}
+
+/// Segments of every kind on a small grid so that they overlap a lot
+fn generate_input(rng: &mut Rng) -> String {
+    let size = rng.gen_range(2..60) as i32;
+    let count = rng.gen_range(1..80);
+    generate_segments(rng, size, count)
+}
+
+/// `count` segments within a `size` by `size` grid
+fn generate_segments(rng: &mut Rng, size: i32, count: u64) -> String {
+    let mut coordinate = || rng.gen_range(0..size as u64) as i32;
+
+    let lines = (0..count)
+        .map(|_| {
+            let (x, y) = (coordinate(), coordinate());
+            let (mut x2, mut y2) = (coordinate(), coordinate());
+            match coordinate() % 4 {
+                0 => x2 = x,
+                1 => y2 = y,
+                // a 45° diagonal, clamped to the grid
+                2 => {
+                    let length = (x2 - x).abs().min(size - 1 - y).min(y);
+                    x2 = x + length * (x2 - x).signum();
+                    y2 = y + length * if y2 < y { -1 } else { 1 };
                 }
+                _ => {}
             }
+            format!("{},{} -> {},{}", x, y, x2, y2)
+        })
+        .collect::<Vec<_>>();
+
+    lines.join("\n")
+}
+
+/// Merging intervals is the fast path, going through the points with either
+/// storage is the original solution kept to check it
+fn part1_implementations() -> Implementations<str, Result<usize, String>> {
+    Implementations::new("result#1 (axis)")
+        .register("intervals", |data| Ok(part1(data)))
+        .register("sparse", |data| {
+            count_points(data, Mode::Axis, Storage::Sparse).map(|(count, _)| count)
+        })
+        .register("dense", |data| {
+            count_points(data, Mode::Axis, Storage::Dense).map(|(count, _)| count)
+        })
+}
 
-            acc
+fn part2_implementations() -> Implementations<str, Result<usize, String>> {
+    Implementations::new("result#2 (diagonal)")
+        .register("intervals", |data| Ok(part2(data)))
+        .register("sparse", |data| {
+            count_points(data, Mode::Diagonal, Storage::Sparse).map(|(count, _)| count)
         })
+        .register("dense", |data| {
+            count_points(data, Mode::Diagonal, Storage::Dense).map(|(count, _)| count)
+        })
+}
+
+/// Times both storages on generated inputs of growing grids, dense storage
+/// being skipped over its limit
+fn bench(mode: Mode) -> Result<String, String> {
+    let mut rng = Rng::new(0);
+    let mut out = String::from("size   segments  cells       auto    sparse      dense\n");
+
+    // the last grid is over the dense limit, auto switches to sparse there
+    for size in [100, 1000, 4000, 10000] {
+        let data = generate_segments(&mut rng, size, 500);
+        let bounds = Bounds::of(&kept_pairs(&data, mode)).ok_or("No segments generated")?;
+
+        let time = |storage| {
+            let start = Instant::now();
+            count_points(&data, mode, storage).map(|(count, _)| (count, start.elapsed()))
+        };
+        let (sparse, sparse_elapsed) = time(Storage::Sparse)?;
+        let dense_elapsed = match Storage::Dense.resolve(bounds) {
+            Ok(_) => {
+                let (dense, dense_elapsed) = time(Storage::Dense)?;
+                if sparse != dense {
+                    return Err(format!(
+                        "Storages disagree on a {} grid: sparse {}, dense {}",
+                        size, sparse, dense
+                    ));
+                }
+                format!("{:.2?}", dense_elapsed)
+            }
+            Err(_) => "too large".to_string(),
+        };
+
+        writeln!(
+            out,
+            "{:<6} {:<9} {:<11} {:<7} {:<11} {}",
+            size,
+            500,
+            bounds
+                .area()
+                .map_or("overflow".to_string(), |area| area.to_string()),
+            Storage::Auto.resolve(bounds)?.to_string(),
+            format!("{:.2?}", sparse_elapsed),
+            dense_elapsed
+        )
+        .unwrap();
+    }
+
+    Ok(out)
+}
+
+fn part1(data: &str) -> usize {
+    count_overlaps(data, Mode::Axis)
+}
+
+fn part2(data: &str) -> usize {
+    count_overlaps(data, Mode::Diagonal)
+}
+
+struct Options {
+    mode: Mode,
+    input: Option<String>,
+    at_least: usize,
+    point: Option<Point>,
+    storage: Option<Storage>,
 }
 
-fn main() {
+/// `[--mode axis|diagonal|any] [--input <file>] [--at-least <k>] [--point <x,y>]
+/// [--storage auto|dense|sparse]`, overlaps are counted by merging intervals unless
+/// a storage is given
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        mode: Mode::Any,
+        input: None,
+        at_least: 2,
+        point: None,
+        storage: None,
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        let invalid = |value: &str| format!("Invalid value for {}: {}", arg, value);
+
+        match arg.as_str() {
+            "--mode" => options.mode = value()?.parse()?,
+            "--storage" => options.storage = Some(value()?.parse()?),
+            "--at-least" => {
+                let value = value()?;
+                options.at_least = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--point" => {
+                let value = value()?;
+                options.point = Some(parse_tuple(value).map_err(|_| invalid(value))?);
+            }
+            "--input" => options.input = Some(Runner::read_input(value()?)?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn day_command(data: &str, name: &str, args: &[String]) -> Result<String, String> {
+    let options = parse_options(args)?;
+    let input = options.input.as_deref().unwrap_or(data);
+    let mut out = format!("mode: {}\n", options.mode);
+
+    match (name, options.storage) {
+        ("overlaps", None) => {
+            writeln!(out, "overlaps: {}", count_overlaps(input, options.mode)).unwrap();
+            return Ok(out);
+        }
+        ("overlaps", Some(storage)) => {
+            let (count, storage) = count_points(input, options.mode, storage)?;
+            writeln!(out, "storage: {}", storage).unwrap();
+            writeln!(out, "overlaps: {}", count).unwrap();
+            return Ok(out);
+        }
+        ("bench", _) => return bench(options.mode).map(|table| out + &table),
+        _ => {}
+    }
+
+    let map = VentMap::new(&parse_lines(input), options.mode);
+    match name {
+        "hotspots" => {
+            for (point, count) in map.at_least(options.at_least) {
+                writeln!(out, "{} {}", point, count).unwrap();
+            }
+        }
+        "max" => match map.max_overlap() {
+            Some((count, points)) => {
+                let points = points.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+                writeln!(out, "max overlap: {} at {}", count, points.join(" ")).unwrap();
+            }
+            None => writeln!(out, "no vents").unwrap(),
+        },
+        "covering" => {
+            let point = options.point.ok_or("Missing --point")?;
+            let segments = map.covering(&point).iter().map(|idx| idx.to_string());
+            writeln!(
+                out,
+                "{}: {}",
+                point,
+                segments.collect::<Vec<_>>().join(", ")
+            )
+            .unwrap();
+        }
+        "pairs" => {
+            for (a, b) in map.intersecting_pairs() {
+                writeln!(out, "{} {}", a, b).unwrap();
+            }
+        }
+        _ => return Err(format!("Unknown command: {}", name)),
+    }
+
+    Ok(out)
+}
+
+fn main() -> ExitCode {
     let _data = include_str!("data_small.txt");
     let data = include_str!("data.txt");
-    let result = part1(data);
-    println!("result#1: {}", result);
-    let result = part2(data);
-    println!("result#2: {}", result);
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match runner.command() {
+        Command::Day { name, args } => runner.print_day_output(day_command(data, &name, &args)),
+        Command::Diff { samples, seed } => {
+            let mut rng = Rng::new(seed);
+            let mut inputs = vec![data.to_string()];
+            inputs.extend((0..samples).map(|_| generate_input(&mut rng)));
+
+            let parts = [&part1_implementations(), &part2_implementations()];
+            if registry::diff::<str, _, _>(&parts, &inputs) > 0 {
+                runner.mark_failed();
+            }
+        }
+        _ => {
+            runner.run("result#1 (axis)", move |_| Ok(part1(data)));
+            runner.run("result#2 (diagonal)", move |_| Ok(part2(data)));
+        }
+    }
+
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -170,4 +507,210 @@ mod test {
         let result = part2(data);
         assert_eq!(result, 12);
     }
+
+    #[test]
+    fn any_slope() {
+        let pair = parse_line("0,0 -> 6,4").unwrap();
+        assert_eq!(line_to_points(&pair), None);
+        assert_eq!(line_to_points_advanced(&pair), None);
+        assert_eq!(
+            line_to_points_any(&pair),
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 3, y: 2 },
+                Point { x: 6, y: 4 }
+            ]
+        );
+
+        let pair = parse_line("5,5 -> 5,5").unwrap();
+        assert_eq!(line_to_points_any(&pair), vec![Point { x: 5, y: 5 }]);
+        // wider than i32::MAX, with the origin as midpoint
+        let pair = parse_line("-2000000000,0 -> 2000000000,2").unwrap();
+        assert_eq!(
+            line_to_points_any(&pair),
+            vec![
+                Point {
+                    x: -2000000000,
+                    y: 0
+                },
+                Point { x: 0, y: 1 },
+                Point {
+                    x: 2000000000,
+                    y: 2
+                }
+            ]
+        );
+        let data = "-2000000000,0 -> 2000000000,2\n0,-5 -> 0,5";
+        let map = VentMap::new(&parse_lines(data), Mode::Any);
+        assert_eq!(map.at_least(2).len(), count_overlaps(data, Mode::Any));
+        assert_eq!(map.covering(&Point { x: 0, y: 1 }), &[0, 1]);
+
+        let pair = parse_line("4,1 -> 1,4").unwrap();
+        assert_eq!(
+            line_to_points_any(&pair),
+            line_to_points_advanced(&pair)
+                .unwrap()
+                .into_iter()
+                .rev()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn modes() {
+        let data = include_str!("data_small.txt");
+        assert_eq!(count_overlaps(data, Mode::Any), 12);
+
+        // the steep segment crosses the horizontal one on the lattice point (2,4)
+        let data = "0,4 -> 6,4\n0,0 -> 4,8\n1,1 -> 1,1";
+        assert_eq!(count_overlaps(data, Mode::Axis), 0);
+        assert_eq!(count_overlaps(data, Mode::Any), 1);
+        assert_eq!("any".parse(), Ok(Mode::Any));
+        assert!("steep".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn intervals_match_points() {
+        let mut rng = Rng::new(5);
+        let mut inputs = (0..200)
+            .map(|_| generate_input(&mut rng))
+            .collect::<Vec<_>>();
+        inputs.push(include_str!("data.txt").to_string());
+        inputs.push("5,5 -> 5,5\n5,5 -> 5,5\n3,3 -> 7,7\n7,3 -> 3,7".to_string());
+
+        for input in &inputs {
+            for mode in [Mode::Axis, Mode::Diagonal, Mode::Any] {
+                let count = count_overlaps(input, mode);
+                assert_eq!(
+                    count_points(input, mode, Storage::Sparse),
+                    Ok((count, Storage::Sparse)),
+                    "{mode} {input}"
+                );
+                assert_eq!(
+                    count_points(input, mode, Storage::Dense),
+                    Ok((count, Storage::Dense)),
+                    "{mode} {input}"
+                );
+            }
+        }
+        assert_eq!(
+            part1_implementations().check(&inputs[0]).map(|_| ()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn single_point_segments() {
+        let pair = &parse_lines("5,5 -> 5,5")[0];
+        assert_eq!(
+            line_to_points_advanced(pair),
+            Some(vec![Point { x: 5, y: 5 }])
+        );
+
+        assert_eq!(part2("5,5 -> 5,5"), 0);
+        assert_eq!(part2("5,5 -> 5,5\n5,5 -> 5,5"), 1);
+        assert_eq!(
+            count_points("5,5 -> 5,5\n3,3 -> 7,7", Mode::Diagonal, Storage::Sparse),
+            Ok((1, Storage::Sparse))
+        );
+    }
+
+    #[test]
+    fn huge_coordinates() {
+        let data =
+            "0,0 -> 0,5000000\n0,1000000 -> 0,9000000\n-3000000,2000000 -> 3000000,2000000\n\
+                    -2000000000,-2000000000 -> 2000000000,2000000000";
+        // the vertical overlap, which the horizontal line crosses. The diagonal
+        // adds the origin and its crossing with the horizontal line.
+        assert_eq!(part1(data), 4000001);
+        assert_eq!(part2(data), 4000003);
+        assert_eq!(count_overlaps(data, Mode::Any), 4000003);
+    }
+
+    #[test]
+    fn vent_map() {
+        let pairs = parse_lines(include_str!("data_small.txt"));
+        let map = VentMap::new(&pairs, Mode::Diagonal);
+
+        assert_eq!(map.at_least(2).len(), part2(include_str!("data_small.txt")));
+        assert_eq!(
+            map.max_overlap(),
+            Some((3, vec![&Point { x: 4, y: 4 }, &Point { x: 6, y: 4 }]))
+        );
+        assert_eq!(map.covering(&Point { x: 4, y: 4 }), &[1, 2, 8]);
+        assert_eq!(map.covering(&Point { x: 100, y: 4 }), &[] as &[usize]);
+
+        let axis = VentMap::new(&pairs, Mode::Axis);
+        assert_eq!(axis.covering(&Point { x: 4, y: 4 }), &[2]);
+        assert_eq!(axis.max_overlap().map(|(count, _)| count), Some(2));
+    }
+
+    #[test]
+    fn intersecting_pairs() {
+        let data = "0,0 -> 4,0\n2,0 -> 2,3\n0,3 -> 3,0\n9,9 -> 9,9\n0,1 -> 0,5\n0,5 -> 0,1";
+        let map = VentMap::new(&parse_lines(data), Mode::Any);
+
+        assert_eq!(
+            map.intersecting_pairs(),
+            vec![(0, 1), (0, 2), (1, 2), (2, 4), (2, 5), (4, 5)]
+        );
+        assert_eq!(map.covering(&Point { x: 2, y: 1 }), &[1, 2]);
+        assert_eq!(map.at_least(3), vec![(&Point { x: 0, y: 3 }, 3)]);
+    }
+
+    #[test]
+    fn storage() {
+        let pairs = parse_lines("3,4 -> 3,9\n-2,5 -> 8,5");
+        let bounds = Bounds::of(&pairs).unwrap();
+        assert_eq!(bounds.area(), Some(11 * 6));
+        assert_eq!(Storage::Auto.resolve(bounds), Ok(Storage::Dense));
+        assert_eq!(Storage::Sparse.resolve(bounds), Ok(Storage::Sparse));
+
+        let pairs = parse_lines("0,0 -> 0,1000000\n1000000,0 -> 1000000,1000000");
+        let bounds = Bounds::of(&pairs).unwrap();
+        assert_eq!(Storage::Auto.resolve(bounds), Ok(Storage::Sparse));
+        assert_eq!(
+            Storage::Dense.resolve(bounds),
+            Err(
+                "A 1000001x1000001 bounding box is too large for dense storage, \
+                 the limit is 67108864 cells"
+                    .to_string()
+            )
+        );
+
+        // the full i32 range has more cells than a u64 holds, both segments
+        // only go through their ends
+        let data = "-2147483648,-2147483648 -> 2147483647,2147483646\n0,0 -> 1,2147483647";
+        let bounds = Bounds::of(&parse_lines(data)).unwrap();
+        assert_eq!(bounds.area(), None);
+        assert_eq!(Storage::Auto.resolve(bounds), Ok(Storage::Sparse));
+        assert_eq!(
+            count_points(data, Mode::Any, Storage::Auto),
+            Ok((0, Storage::Sparse))
+        );
+        assert!(count_points(data, Mode::Any, Storage::Dense).is_err());
+
+        // beyond u8, a cell covered 300 times still counts once
+        let data = vec!["1,1 -> 1,2"; 300].join("\n");
+        assert_eq!(
+            count_points(&data, Mode::Axis, Storage::Dense),
+            Ok((2, Storage::Dense))
+        );
+        assert_eq!(
+            count_points("", Mode::Axis, Storage::Dense),
+            Ok((0, Storage::Dense))
+        );
+
+        // the far slope is dropped in axis mode, so is its bounding box
+        let data = "0,0 -> 5,0\n2,0 -> 2,3\n0,0 -> 100000000,1";
+        assert_eq!(
+            count_points(data, Mode::Axis, Storage::Auto),
+            Ok((1, Storage::Dense))
+        );
+        assert_eq!(
+            count_points(data, Mode::Any, Storage::Auto),
+            Ok((2, Storage::Sparse))
+        );
+        assert_eq!("dense".parse(), Ok(Storage::Dense));
+    }
 }
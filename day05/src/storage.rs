@@ -0,0 +1,131 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{Pair, Point};
+
+/// Largest bounding box, in cells, kept in a dense array. One byte per
+/// cell, so 64 MiB at most.
+const DENSE_LIMIT: u64 = 1 << 26;
+
+/// Where the count of every point is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Storage {
+    /// Dense when the bounding box is at most `DENSE_LIMIT` cells
+    Auto,
+    /// A `u8` per cell of the bounding box, which can't be over
+    /// `DENSE_LIMIT` cells
+    Dense,
+    /// A map entry per covered point
+    Sparse,
+}
+
+impl FromStr for Storage {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "auto" => Ok(Storage::Auto),
+            "dense" => Ok(Storage::Dense),
+            "sparse" => Ok(Storage::Sparse),
+            _ => Err(format!("Unknown storage: {}", input)),
+        }
+    }
+}
+
+impl fmt::Display for Storage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Storage::Auto => write!(f, "auto"),
+            Storage::Dense => write!(f, "dense"),
+            Storage::Sparse => write!(f, "sparse"),
+        }
+    }
+}
+
+/// The smallest rectangle holding every segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    min_x: i64,
+    min_y: i64,
+    width: u64,
+    height: u64,
+}
+
+impl Bounds {
+    pub fn of(pairs: &[Pair]) -> Option<Self> {
+        let points = pairs.iter().flat_map(|pair| [&pair.0, &pair.1]);
+        let min_x = points.clone().map(|p| p.x).min()? as i64;
+        let min_y = points.clone().map(|p| p.y).min()? as i64;
+        let max_x = points.clone().map(|p| p.x).max()? as i64;
+        let max_y = points.map(|p| p.y).max()? as i64;
+
+        Some(Bounds {
+            min_x,
+            min_y,
+            width: (max_x - min_x + 1) as u64,
+            height: (max_y - min_y + 1) as u64,
+        })
+    }
+
+    /// Cells in the rectangle, `None` when they don't fit a `u64`
+    pub fn area(&self) -> Option<u64> {
+        self.width.checked_mul(self.height)
+    }
+
+    fn offset(&self, point: &Point) -> usize {
+        let x = (point.x as i64 - self.min_x) as usize;
+        let y = (point.y as i64 - self.min_y) as usize;
+        y * self.width as usize + x
+    }
+}
+
+impl Storage {
+    /// `Dense` or `Sparse`, whichever is used for segments within `bounds`.
+    /// Fails when dense storage is forced on more than `DENSE_LIMIT` cells.
+    pub fn resolve(self, bounds: Bounds) -> Result<Storage, String> {
+        let fits = bounds.area().is_some_and(|area| area <= DENSE_LIMIT);
+
+        match self {
+            Storage::Auto if fits => Ok(Storage::Dense),
+            Storage::Auto => Ok(Storage::Sparse),
+            Storage::Dense if !fits => Err(format!(
+                "A {}x{} bounding box is too large for dense storage, the limit is {} cells",
+                bounds.width, bounds.height, DENSE_LIMIT
+            )),
+            forced => Ok(forced),
+        }
+    }
+}
+
+/// Points appearing at least twice. `storage` is the one `Storage::resolve`
+/// picked for `bounds`, dense storage being allocated for all of them.
+pub fn count_at_least_twice(
+    points: impl Iterator<Item = Point>,
+    bounds: Bounds,
+    storage: Storage,
+) -> usize {
+    match storage {
+        Storage::Dense => {
+            let mut cells = vec![0u8; (bounds.width * bounds.height) as usize];
+            points.fold(0, |mut acc, point| {
+                let cell = &mut cells[bounds.offset(&point)];
+                if *cell == 1 {
+                    acc += 1;
+                }
+                *cell = cell.saturating_add(1);
+                acc
+            })
+        }
+        _ => {
+            let mut board: HashMap<Point, u32> = HashMap::new();
+            points.fold(0, |mut acc, point| {
+                let new_value = board.entry(point).or_insert(0);
+                *new_value += 1;
+
+                if *new_value == 2 {
+                    acc += 1;
+                }
+                acc
+            })
+        }
+    }
+}
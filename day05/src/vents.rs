@@ -0,0 +1,61 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{rasterise, Mode, Pair, Point};
+
+/// The segments covering every point, segments being numbered in input order
+pub struct VentMap {
+    covering: HashMap<Point, Vec<usize>>,
+}
+
+impl VentMap {
+    pub fn new(pairs: &[Pair], mode: Mode) -> Self {
+        let mut covering: HashMap<Point, Vec<usize>> = HashMap::new();
+
+        for (idx, pair) in pairs.iter().enumerate() {
+            for point in rasterise(pair, mode).unwrap_or_default() {
+                covering.entry(point).or_default().push(idx);
+            }
+        }
+
+        VentMap { covering }
+    }
+
+    /// Points covered by at least `k` segments with their count, sorted
+    pub fn at_least(&self, k: usize) -> Vec<(&Point, usize)> {
+        let mut points = self
+            .covering
+            .iter()
+            .map(|(point, segments)| (point, segments.len()))
+            .filter(|&(_, count)| count >= k)
+            .collect::<Vec<_>>();
+        points.sort();
+        points
+    }
+
+    /// The highest count and every point that has it
+    pub fn max_overlap(&self) -> Option<(usize, Vec<&Point>)> {
+        let max = self.covering.values().map(Vec::len).max()?;
+        let points = self.at_least(max).into_iter().map(|(point, _)| point);
+
+        Some((max, points.collect()))
+    }
+
+    pub fn covering(&self, point: &Point) -> &[usize] {
+        self.covering.get(point).map_or(&[], Vec::as_slice)
+    }
+
+    /// Pairs of segments sharing at least one point, the lower index first
+    pub fn intersecting_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = BTreeSet::new();
+
+        for segments in self.covering.values() {
+            for (idx, &a) in segments.iter().enumerate() {
+                for &b in &segments[idx + 1..] {
+                    pairs.insert((a, b));
+                }
+            }
+        }
+
+        pairs.into_iter().collect()
+    }
+}
@@ -1,4 +1,10 @@
-use std::collections::LinkedList;
+use std::{collections::LinkedList, process::ExitCode, time::Duration};
+
+use utils::{
+    registry::{self, Implementations},
+    rng::Rng,
+    runner::{Command, Runner},
+};
 
 type State = Vec<u8>;
 
@@ -30,14 +36,7 @@ fn part1(data: &str, iterations: i32) -> usize {
 }
 
 fn part2(data: &str, iterations: u32) -> u64 {
-    let state = parse_data(data);
-
-    let mut list = [0; 9];
-
-    for entry in state {
-        list[entry as usize] += 1;
-    }
-
+    let list = count_fish(&parse_data(data));
     let mut list: LinkedList<u64> = LinkedList::from_iter(list);
 
     for _ in 0..iterations {
@@ -60,14 +59,81 @@ fn part2(data: &str, iterations: u32) -> u64 {
     list.iter().sum()
 }
 
-fn main() {
+fn count_fish(state: &[u8]) -> [u64; 9] {
+    let mut counts = [0; 9];
+
+    for &entry in state {
+        counts[entry as usize] += 1;
+    }
+
+    counts
+}
+
+// Same idea as part2, but rotating a fixed array instead of a linked list
+fn part2_array(data: &str, iterations: u32) -> u64 {
+    let mut counts = count_fish(&parse_data(data));
+
+    for _ in 0..iterations {
+        counts.rotate_left(1);
+        counts[6] += counts[8];
+    }
+
+    counts.iter().sum()
+}
+
+fn with_counting(part: Implementations<str, u64>, iterations: u32) -> Implementations<str, u64> {
+    part.register("linked_list", move |data| part2(data, iterations))
+        .register("array", move |data| part2_array(data, iterations))
+}
+
+fn generate_input(rng: &mut Rng) -> String {
+    let fish_count = rng.gen_range(1..300);
+    let timers = (0..fish_count)
+        .map(|_| rng.gen_range(0..9).to_string())
+        .collect::<Vec<_>>();
+
+    timers.join(",")
+}
+
+fn main() -> ExitCode {
     let data = include_str!("data_small.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let iterations = 12;
+    let part1 = with_counting(
+        Implementations::new("result#1")
+            .register("naive", move |data| part1(data, iterations as i32) as u64),
+        iterations,
+    );
+    let part2 = with_counting(Implementations::new("result#2"), iterations);
+
+    match runner.command() {
+        Command::Solve | Command::Report { .. } => {
+            runner.run("result#1", move |_| Ok(part1.solve(data)));
+            runner.run("result#2", move |_| Ok(part2.solve(data)));
+        }
+        Command::Diff { samples, seed } => {
+            let mut rng = Rng::new(seed);
+            let mut inputs = vec![data.to_string()];
+            inputs.extend((0..samples).map(|_| generate_input(&mut rng)));
 
-    let result = part1(data, 12);
-    println!("result#1: {}", result);
+            if registry::diff::<str, _, _>(&[&part1, &part2], &inputs) > 0 {
+                runner.mark_failed();
+            }
+        }
+        Command::Day { name, .. } => {
+            eprintln!("Unknown command: {name}");
+            runner.mark_failed();
+        }
+    }
 
-    let result = part2(data, 12);
-    println!("result#2: {}", result);
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -92,4 +158,13 @@ mod test {
         let result = part2(data, 256);
         assert_eq!(result, 26984457539);
     }
+    #[test]
+    fn implementations_agree() {
+        let data = include_str!("data_small.txt");
+        let part = with_counting(
+            Implementations::new("result#1").register("naive", |data| part1(data, 80) as u64),
+            80,
+        );
+        assert_eq!(part.check(data), Ok(5934));
+    }
 }
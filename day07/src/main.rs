@@ -1,3 +1,7 @@
+use std::{process::ExitCode, time::Duration};
+
+use utils::runner::Runner;
+
 fn parse_data(data: &str) -> Vec<i32> {
     data.lines()
         .next()
@@ -7,7 +11,7 @@ fn parse_data(data: &str) -> Vec<i32> {
         .collect()
 }
 
-fn median(numbers: &mut Vec<i32>) -> i32 {
+fn median(numbers: &mut [i32]) -> i32 {
     numbers.sort();
     let mid = numbers.len() / 2;
     numbers[mid]
@@ -42,7 +46,7 @@ fn part2(data: &str) -> u32 {
     let mut min = *initial_positions.iter().min().unwrap();
     let mut max = *initial_positions.iter().max().unwrap();
 
-    let mut min_sum = u32::max_value();
+    let mut min_sum = u32::MAX;
 
     while min <= max {
         let mid = (min + max) / 2;
@@ -62,13 +66,20 @@ fn part2(data: &str) -> u32 {
     min_sum
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |_| Ok(part2(data)));
 
-    let result = part1(data);
-    println!("result#1: {}", result);
-    let result = part2(data);
-    println!("result#2: {}", result);
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -1,3 +1,7 @@
+use std::{process::ExitCode, time::Duration};
+
+use utils::runner::Runner;
+
 type Entry = (Vec<String>, Vec<String>);
 
 fn process_entry(entry: &str) -> Vec<String> {
@@ -67,14 +71,20 @@ fn part2(data: &str) -> u32 {
     parse_input(data).iter().map(decode).sum()
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
-    let result = part1(data);
-    println!("result#1: {}", result);
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let data = include_str!("data.txt");
-    let result = part2(data);
-    println!("result#2: {}", result);
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |_| Ok(part2(data)));
+
+    runner.finish()
 }
 
 #[cfg(test)]
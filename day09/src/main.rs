@@ -1,4 +1,6 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, process::ExitCode, time::Duration};
+
+use utils::runner::Runner;
 
 type MapCoords = (usize, usize);
 type Map = Vec<Vec<u32>>;
@@ -62,8 +64,7 @@ impl HeightMap {
         let mut stack: Vec<MapCoords> = vec![low_point];
         let mut visited: HashSet<MapCoords> = HashSet::new();
 
-        while !stack.is_empty() {
-            let curr_coords = stack.pop().unwrap();
+        while let Some(curr_coords) = stack.pop() {
             let (row_idx, col_idx) = curr_coords;
 
             visited.insert(curr_coords);
@@ -127,14 +128,21 @@ fn part2(data: &str) -> u32 {
     height_map.calc_basins_risk_level()
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
-    let result = part1(data);
-    println!("result#1: {}", result);
+    let data_example = include_str!("data_example.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |_| Ok(part2(data_example)));
 
-    let data = include_str!("data_example.txt");
-    let result = part2(data);
-    println!("result#2: {}", result);
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use lazy_static::lazy_static;
-use std::collections::HashMap;
+use std::{collections::HashMap, process::ExitCode, time::Duration};
+use utils::runner::Runner;
 
 lazy_static! {
     static ref BRACKET_MAP: HashMap<char, char> = [(')', '('), (']', '['), ('}', '{'), ('>', '<')]
@@ -81,14 +82,20 @@ fn part2(data: &str) -> u64 {
     result[middle_index]
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let result = part1(data);
-    println!("result#1: {}", result);
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |_| Ok(part2(data)));
 
-    let result = part2(data);
-    println!("result#2: {}", result);
+    runner.finish()
 }
 
 #[cfg(test)]
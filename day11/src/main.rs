@@ -1,9 +1,15 @@
+use std::{process::ExitCode, time::Duration};
+
+use utils::runner::{CancelToken, Cancelled, Runner};
+
 mod energy_grid {
     use std::{
         collections::{HashSet, VecDeque},
         fmt::{self, Display},
     };
 
+    use utils::runner::{CancelToken, Cancelled};
+
     #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
     struct GridPos {
         x: usize,
@@ -106,14 +112,15 @@ mod energy_grid {
                 .all(|row| row.iter().all(|&value| value == 0))
         }
 
-        pub fn find_sync_step(&mut self) -> u32 {
+        pub fn find_sync_step(&mut self, token: &CancelToken) -> Result<u32, Cancelled> {
             let mut step_count = 0;
             while !self.is_all_zeros() {
+                token.check()?;
                 self.step();
                 step_count += 1;
             }
 
-            step_count
+            Ok(step_count)
         }
     }
 
@@ -157,21 +164,27 @@ fn part1(data: &str) -> u32 {
     grid.flash_count
 }
 
-fn part2(data: &str) -> u32 {
+fn part2(data: &str, token: &CancelToken) -> Result<u32, Cancelled> {
     let data = parse_input(data);
 
     let mut grid = energy_grid::EnergyGrid::new(data);
-    grid.find_sync_step()
+    grid.find_sync_step(token)
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let result = part1(data);
-    println!("result#1: {}", result);
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |token| part2(data, token));
 
-    let result = part2(data);
-    println!("result#2: {}", result);
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -187,7 +200,7 @@ mod test {
     #[test]
     fn part2_example() {
         let data = include_str!("data_example.txt");
-        let result = part2(data);
+        let result = part2(data, &CancelToken::new()).unwrap();
         assert_eq!(result, 195);
     }
 }
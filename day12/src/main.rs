@@ -1,8 +1,12 @@
 use std::{
     collections::{HashMap, VecDeque},
+    process::ExitCode,
     str::FromStr,
+    time::Duration,
 };
 
+use utils::runner::{CancelToken, Cancelled, Runner};
+
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq, Hash, Clone)]
 enum Node {
     Start,
@@ -64,12 +68,18 @@ fn build_adjacency_list(connections: Vec<(String, String)>) -> AdjacencyList {
     adjacency_list
 }
 
-fn count_connections(adjacency_list: &AdjacencyList, visits_allowed: u32) -> u32 {
+fn count_connections(
+    adjacency_list: &AdjacencyList,
+    visits_allowed: u32,
+    token: &CancelToken,
+) -> Result<u32, Cancelled> {
     let mut path_count = 0;
     let mut queue: VecDeque<(&Node, Vec<&Node>, u32)> = VecDeque::new();
     queue.push_back((&Node::Start, Vec::new(), 0));
 
     while let Some((node, path, repeated_visits_count)) = queue.pop_front() {
+        token.check()?;
+
         for neighbor in adjacency_list.get(node).unwrap() {
             match neighbor {
                 Node::CaveS(_) => {
@@ -98,29 +108,39 @@ fn count_connections(adjacency_list: &AdjacencyList, visits_allowed: u32) -> u32
         }
     }
 
-    path_count
+    Ok(path_count)
 }
 
-fn part1(data: &str) -> u32 {
+fn part1(data: &str, token: &CancelToken) -> Result<u32, Cancelled> {
     let connections_raw = parse_data(data);
     let adjacency_list = build_adjacency_list(connections_raw);
-    count_connections(&adjacency_list, 0)
+    count_connections(&adjacency_list, 0, token)
 }
 
-fn part2(data: &str) -> u32 {
+fn part2(data: &str, token: &CancelToken) -> Result<u32, Cancelled> {
     let connections_raw = parse_data(data);
     let adjacency_list = build_adjacency_list(connections_raw);
-    count_connections(&adjacency_list, 1)
+    count_connections(&adjacency_list, 1, token)
 }
 
-fn main() {
+fn main() -> ExitCode {
     let input = include_str!("data.txt");
-    let result = part1(input);
-    println!("result#1: {result}");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(input).with_cache(env!("SOLVER_VERSION")),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let input = include_str!("data.txt");
-    let result = part2(input);
-    println!("result#2: {result}");
+    runner.run_cached("result#1", input.as_bytes(), move |token| {
+        part1(input, token)
+    });
+    runner.run_cached("result#2", input.as_bytes(), move |token| {
+        part2(input, token)
+    });
+
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -129,28 +149,28 @@ mod test {
     #[test]
     fn part1_example() {
         let input = include_str!("data_example.txt");
-        let result = part1(input);
+        let result = part1(input, &CancelToken::new()).unwrap();
         assert_eq!(result, 10);
     }
 
     #[test]
     fn part1_example_2() {
         let input = include_str!("data_example_2.txt");
-        let result = part1(input);
+        let result = part1(input, &CancelToken::new()).unwrap();
         assert_eq!(result, 226);
     }
 
     #[test]
     fn part2_example() {
         let input = include_str!("data_example.txt");
-        let result = part2(input);
+        let result = part2(input, &CancelToken::new()).unwrap();
         assert_eq!(result, 36);
     }
 
     #[test]
     fn part2_example_2() {
         let input = include_str!("data_example_2.txt");
-        let result = part2(input);
+        let result = part2(input, &CancelToken::new()).unwrap();
         assert_eq!(result, 3509);
     }
 }
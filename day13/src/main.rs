@@ -1,9 +1,13 @@
 use std::{
     collections::HashSet,
     fmt::{self, Display},
+    process::ExitCode,
     str::FromStr,
+    time::Duration,
 };
 
+use utils::runner::Runner;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct GridPos {
     x: usize,
@@ -151,7 +155,7 @@ fn part1(data: &str) -> u32 {
     paper_sheet.count_dots()
 }
 
-fn part2(data: &str) {
+fn part2(data: &str) -> String {
     let (mut paper_sheet, instructions) = parse_input(data);
 
     for instruction in &instructions {
@@ -161,16 +165,23 @@ fn part2(data: &str) {
         }
     }
 
-    println!("{}", paper_sheet);
+    paper_sheet.to_string()
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let result = part1(data);
-    println!("result#1: {}", result);
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |_| Ok(part2(data)));
 
-    part2(data);
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, process::ExitCode, time::Duration};
+
+use utils::runner::Runner;
 
 type Pattern = Vec<String>;
 type InsertionDict = HashMap<String, (String, String)>;
@@ -28,7 +30,7 @@ impl<'a> Polymer<'a> {
     }
 
     fn ceil_div(a: u64, b: u64) -> u64 {
-        (a + b - 1) / b
+        a.div_ceil(b)
     }
 
     fn step(&mut self) {
@@ -130,15 +132,20 @@ fn part2(data: &str) -> u64 {
     polymer.count_result()
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(data),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let result = part1(data);
-    println!("result#1: {}", result);
-
-    let result = part2(data);
+    runner.run("result#1", move |_| Ok(part1(data)));
+    runner.run("result#2", move |_| Ok(part2(data)));
 
-    println!("result#2: {}", result);
+    runner.finish()
 }
 
 #[cfg(test)]
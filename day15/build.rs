@@ -0,0 +1,3 @@
+fn main() {
+    utils::cache::emit_solver_version();
+}
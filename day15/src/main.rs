@@ -1,7 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    process::ExitCode,
+    time::Duration,
+};
 
 use priority_queue::DoublePriorityQueue;
-use utils::print_matrix;
+use utils::{print_matrix, runner::Runner};
 
 type Grid = Vec<Vec<u32>>;
 
@@ -96,7 +100,7 @@ impl PathFinder {
 
     Reconsructing path: 1 -> 3b -> 3a -> 5 -> 1
     */
-    fn find_path(&self) -> u32 {
+    fn find_path(&self) -> (u32, Vec<Position>) {
         let mut pq = DoublePriorityQueue::new();
         let mut prev_map: HashMap<Position, Option<Position>> = HashMap::new();
         let mut weights: HashMap<Position, u32> = HashMap::new();
@@ -125,12 +129,14 @@ impl PathFinder {
         }
 
         let path = self.reconstruct_path(&prev_map);
+        let risk = path.iter().skip(1).map(|p| self.grid[p.y][p.x]).sum();
 
-        path.iter().skip(1).map(|p| self.grid[p.y][p.x]).sum()
+        (risk, path)
     }
 
-    fn _visualize_path(matrix: &[Vec<u32>], path: &[Position]) {
-        let mut cloned: Vec<Vec<char>> = matrix
+    fn render_path(&self, path: &[Position]) -> String {
+        let mut cloned: Vec<Vec<char>> = self
+            .grid
             .iter()
             .map(|row| {
                 row.iter()
@@ -143,7 +149,15 @@ impl PathFinder {
             cloned[node.y][node.x] = '-';
         }
 
-        print_matrix(&cloned);
+        cloned
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| format!("{cell} "))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
     fn _visualize_point_map(&self, danger_table: &HashMap<Position, u32>) {
@@ -193,24 +207,36 @@ fn part1(data: &str) -> u32 {
     let matrix = parse_input(data);
 
     let path_finder = PathFinder::new(matrix);
-    path_finder.find_path()
+    path_finder.find_path().0
 }
 
 fn part2(data: &str) -> u32 {
     let matrix_extended = extend_matrix(parse_input(data));
 
     let path_finder = PathFinder::new(matrix_extended);
-    path_finder.find_path()
+    path_finder.find_path().0
 }
 
-fn main() {
+fn main() -> ExitCode {
     let data = include_str!("data.txt");
+    let runner = match Runner::from_args(Duration::from_secs(30)) {
+        Ok(runner) => runner.with_input(data).with_cache(env!("SOLVER_VERSION")),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    runner.run_cached("result#1", data.as_bytes(), move |_| Ok(part1(data)));
+    runner.run_cached("result#2", data.as_bytes(), move |_| Ok(part2(data)));
 
-    let res = part1(data);
-    println!("result#1: {res}");
+    runner.render("Lowest risk path", move || {
+        let path_finder = PathFinder::new(parse_input(data));
+        let (_, path) = path_finder.find_path();
+        path_finder.render_path(&path)
+    });
 
-    let res = part2(data);
-    println!("result#2: {res}");
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -1,4 +1,9 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, process::ExitCode, time::Duration};
+
+use utils::{
+    bits::{BitReader, BitsError},
+    runner::Runner,
+};
 
 #[repr(u8)]
 #[derive(Debug)]
@@ -62,50 +67,37 @@ enum Packet {
     },
 }
 
-fn parse_packet(packet: &[bool], pointer: &mut usize) -> Result<Packet, String> {
-    let idx = *pointer;
-    let version = bits_to_decimal(&packet[idx..idx + 3]) as u32;
-    let type_id = bits_to_decimal(&packet[idx + 3..idx + 6]) as u8;
+fn parse_packet(reader: &mut BitReader) -> Result<Packet, BitsError> {
+    let version = reader.read(3)? as u32;
+    let type_id = reader.read(3)? as u8;
 
     if type_id == 4 {
-        let mut packet_end_pointer: usize = idx + 6;
-        let mut data_bits = Vec::new();
+        let mut data = 0;
 
         loop {
-            for &bit in &packet[packet_end_pointer + 1..packet_end_pointer + 5] {
-                data_bits.push(bit);
-            }
+            let has_more_groups = reader.read_bit()?;
+            data = (data << 4) | reader.read(4)?;
 
             // last group found
-            if !packet[packet_end_pointer] {
-                *pointer = packet_end_pointer + 5;
+            if !has_more_groups {
                 break;
-            } else {
-                packet_end_pointer += 5
             }
         }
 
-        return Ok(Packet::Literal {
-            version,
-            data: bits_to_decimal(&data_bits),
-        });
+        return Ok(Packet::Literal { version, data });
     }
 
-    let length_type_id = bits_to_decimal(&packet[idx + 6..idx + 7]);
+    let length_type_id = reader.read(1)?;
     let mut children = Vec::new();
 
     if length_type_id == 0 {
-        let subpacket_length = bits_to_decimal(&packet[idx + 7..idx + 22]) as usize;
-        let mut subpacket_payload_start = idx + 22;
-        let subpacket_payload_end = idx + 22 + subpacket_length;
+        let subpacket_length = reader.read(15)? as usize;
+        let subpacket_payload_end = reader.position() + subpacket_length;
 
-        while subpacket_payload_start < subpacket_payload_end {
-            let packet = parse_packet(packet, &mut subpacket_payload_start).unwrap();
-            children.push(packet);
+        while reader.position() < subpacket_payload_end {
+            children.push(parse_packet(reader)?);
         }
 
-        *pointer = subpacket_payload_start;
-
         return Ok(Packet::Operator {
             version,
             tp: OperatorType::from_u8(type_id).unwrap(),
@@ -113,18 +105,12 @@ fn parse_packet(packet: &[bool], pointer: &mut usize) -> Result<Packet, String>
         });
     }
 
-    let mut subpacket_count = bits_to_decimal(&packet[idx + 7..idx + 18]) as usize;
-    let mut subpacket_payload_start = idx + 18;
-
-    while subpacket_count > 0 {
-        let packet = parse_packet(packet, &mut subpacket_payload_start).unwrap();
-        children.push(packet);
+    let subpacket_count = reader.read(11)?;
 
-        subpacket_count -= 1;
+    for _ in 0..subpacket_count {
+        children.push(parse_packet(reader)?);
     }
 
-    *pointer = subpacket_payload_start;
-
     Ok(Packet::Operator {
         version,
         tp: OperatorType::from_u8(type_id).unwrap(),
@@ -133,14 +119,8 @@ fn parse_packet(packet: &[bool], pointer: &mut usize) -> Result<Packet, String>
 }
 
 fn parse_message(packet_str: &str) -> Result<Packet, String> {
-    let binary = packet_str
-        .chars()
-        .filter_map(|c| u8::from_str_radix(&c.to_string(), 16).ok())
-        .flat_map(|num| (0..4).rev().map(move |i| (num & (1 << i)) != 0))
-        .collect::<Vec<_>>();
-
-    let mut pointer = 0;
-    parse_packet(&binary, &mut pointer)
+    let mut reader = BitReader::from_hex(packet_str).map_err(|err| err.to_string())?;
+    parse_packet(&mut reader).map_err(|err| err.to_string())
 }
 
 fn calculate_version(packet: &Packet) -> u32 {
@@ -168,10 +148,6 @@ fn calculate_version(packet: &Packet) -> u32 {
     result
 }
 
-fn bits_to_decimal(bits: &[bool]) -> u64 {
-    bits.iter().fold(0, |out, &bit| (out << 1) | (bit as u64))
-}
-
 fn calculate(packet: &Packet) -> Option<u64> {
     match packet {
         Packet::Literal { data, .. } => Some(*data),
@@ -206,12 +182,20 @@ fn part2(packet_str: &str) -> u64 {
     calculate(&packet).unwrap()
 }
 
-fn main() {
+fn main() -> ExitCode {
     let string = include_str!("data.txt");
-    let result = part1(string);
-    println!("RESULT: {result}");
-    let result = part2(string);
-    println!("RESULT: {result}");
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(string),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    runner.run("result#1", move |_| Ok(part1(string)));
+    runner.run("result#2", move |_| Ok(part2(string)));
+
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, process::ExitCode, time::Duration};
+
+use utils::runner::{CancelToken, Cancelled, Runner};
 
 fn parse_input(input: &str) -> Rect {
     let mut ranges_iter = input
@@ -131,7 +133,7 @@ fn part1(input: &str) -> i64 {
     gaussian(max_velocity)
 }
 
-fn part2(input: &str) -> u32 {
+fn part2(input: &str, token: &CancelToken) -> Result<u32, Cancelled> {
     let target = parse_input(input);
 
     println!("{:?}", target);
@@ -144,19 +146,31 @@ fn part2(input: &str) -> u32 {
 
     for x in min_velocity_x..=max_velocity_x {
         for y in min_velocity_y..=max_velocity_y {
+            token.check()?;
+
             if will_collide(Vec2 { x, y }, &target) {
                 counter += 1;
             }
         }
     }
 
-    counter
+    Ok(counter)
 }
 
-fn main() {
+fn main() -> ExitCode {
     let input = include_str!("data.txt");
-    part1(input);
-    part2(input);
+    let runner = match Runner::from_args(Duration::from_secs(10)) {
+        Ok(runner) => runner.with_input(input),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    runner.run("result#1", move |_| Ok(part1(input)));
+    runner.run("result#2", move |token| part2(input, token));
+
+    runner.finish()
 }
 
 #[cfg(test)]
@@ -172,7 +186,7 @@ mod tests {
     #[test]
     fn part2_examples() {
         let input = include_str!("data_example.txt");
-        let result = part2(input);
+        let result = part2(input, &CancelToken::new()).unwrap();
         assert_eq!(result, 112);
     }
 }
@@ -0,0 +1,195 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode, Stdio},
+};
+
+use utils::report::{self, DayReport, Example};
+
+const TITLE: &str = "Advent of Code 2021";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+#[derive(Debug)]
+struct Options {
+    format: Format,
+    output: Option<PathBuf>,
+    days: Vec<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        format: Format::Markdown,
+        output: None,
+        days: Vec::new(),
+    };
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("markdown") | Some("md") => Format::Markdown,
+                    Some("html") => Format::Html,
+                    other => return Err(format!("Unknown format: {:?}", other)),
+                }
+            }
+            "--output" => {
+                let value = args.next().ok_or("Missing value for --output")?;
+                options.output = Some(PathBuf::from(value));
+            }
+            day if day.starts_with("day") => options.days.push(day.to_string()),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("Report crate should live inside the workspace")
+        .to_path_buf()
+}
+
+fn find_days(root: &Path) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(root).map_err(|err| err.to_string())?;
+
+    let mut days = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("Cargo.toml").exists())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("day"))
+        .collect::<Vec<_>>();
+
+    days.sort();
+    Ok(days)
+}
+
+fn cargo(root: &Path) -> Command {
+    let mut command = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    command.current_dir(root).stderr(Stdio::null());
+    command
+}
+
+fn run_day(root: &Path, day: &str) -> Result<DayReport, String> {
+    let output = env::temp_dir().join(format!("aoc2021-report-{day}.txt"));
+
+    // a day exits with a failure code when a part times out, but it still
+    // writes its report, so the status is not checked here
+    cargo(root)
+        .args(["run", "--release", "--quiet", "-p", day, "--"])
+        .args(["report", "--no-cache", "--output"])
+        .arg(&output)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|err| format!("Failed to run {day}: {err}"))?;
+
+    let text = fs::read_to_string(&output)
+        .map_err(|err| format!("{day} did not produce a report: {err}"))?;
+    let _ = fs::remove_file(&output);
+
+    DayReport::from_text(&text)
+}
+
+fn parse_test_output(output: &str) -> Vec<Example> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("test "))
+        .filter_map(|line| line.rsplit_once(" ... "))
+        .filter(|(_, result)| *result != "ignored")
+        .map(|(name, result)| Example {
+            name: name.to_string(),
+            passed: result == "ok",
+        })
+        .collect()
+}
+
+fn run_examples(root: &Path, day: &str) -> Result<Vec<Example>, String> {
+    let output = cargo(root)
+        .args(["test", "--release", "-p", day])
+        .output()
+        .map_err(|err| format!("Failed to test {day}: {err}"))?;
+
+    Ok(parse_test_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn main() -> ExitCode {
+    let options = parse_args().expect("Failed to parse arguments");
+    let root = workspace_root();
+
+    let days = if options.days.is_empty() {
+        find_days(&root).expect("Failed to list workspace days")
+    } else {
+        options.days
+    };
+
+    let mut reports = Vec::new();
+    let mut failed = false;
+
+    for day in &days {
+        eprintln!("Running {day}...");
+
+        let report = run_day(&root, day).and_then(|mut report| {
+            report.examples = run_examples(&root, day)?;
+            Ok(report)
+        });
+
+        match report {
+            Ok(report) => reports.push(report),
+            Err(err) => {
+                eprintln!("{err}");
+                failed = true;
+            }
+        }
+    }
+
+    let document = match options.format {
+        Format::Markdown => report::to_markdown(TITLE, &reports),
+        Format::Html => report::to_html(TITLE, &reports),
+    };
+
+    match options.output {
+        Some(path) => fs::write(&path, document).expect("Failed to write report"),
+        None => print!("{document}"),
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_test_results() {
+        let output = "\nrunning 3 tests\n\
+            test tests::part1_example ... ok\n\
+            test tests::part2_example ... FAILED\n\
+            test tests::slow ... ignored\n\n\
+            test result: FAILED. 1 passed; 1 failed; 1 ignored\n";
+
+        assert_eq!(
+            parse_test_output(output),
+            vec![
+                Example {
+                    name: "tests::part1_example".to_string(),
+                    passed: true
+                },
+                Example {
+                    name: "tests::part2_example".to_string(),
+                    passed: false
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::{error, fmt};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BitsError {
+    /// Reading would go past the end of the data
+    OutOfBounds {
+        position: usize,
+        requested: usize,
+        len: usize,
+    },
+    /// Seeking to a position past the end of the data
+    SeekOutOfBounds { position: usize, len: usize },
+    /// More bits were requested than the target integer can hold
+    TooWide { requested: usize, max: usize },
+    /// Input contains a character that is not a valid digit
+    InvalidDigit { index: usize, found: char },
+}
+
+impl fmt::Display for BitsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BitsError::OutOfBounds {
+                position,
+                requested,
+                len,
+            } => write!(
+                f,
+                "Can't access {requested} bit(s) at position {position}: only {len} bit(s) available"
+            ),
+            BitsError::SeekOutOfBounds { position, len } => {
+                write!(
+                    f,
+                    "Can't seek to position {position}: only {len} bit(s) available"
+                )
+            }
+            BitsError::TooWide { requested, max } => {
+                write!(f, "Can't fit {requested} bits into a {max}-bit integer")
+            }
+            BitsError::InvalidDigit { index, found } => {
+                write!(f, "Invalid digit '{found}' at index {index}")
+            }
+        }
+    }
+}
+
+impl error::Error for BitsError {}
+
+/// Sequential MSB-first reader over a bit string
+#[derive(Debug, Clone)]
+pub struct BitReader {
+    bytes: Vec<u8>,
+    len: usize,
+    position: usize,
+}
+
+impl BitReader {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        BitReader {
+            bytes: bytes.to_vec(),
+            len: bytes.len() * 8,
+            position: 0,
+        }
+    }
+
+    pub fn from_bits(bits: &[bool]) -> Self {
+        let mut bytes = vec![0; bits.len().div_ceil(8)];
+
+        for (idx, &bit) in bits.iter().enumerate() {
+            if bit {
+                bytes[idx / 8] |= 0x80 >> (idx % 8);
+            }
+        }
+
+        BitReader {
+            bytes,
+            len: bits.len(),
+            position: 0,
+        }
+    }
+
+    /// Every hex digit expands into 4 bits. Surrounding whitespace is ignored.
+    pub fn from_hex(input: &str) -> Result<Self, BitsError> {
+        let input = input.trim();
+        let mut bytes = vec![0; input.len().div_ceil(2)];
+
+        for (idx, c) in input.chars().enumerate() {
+            let nibble = c.to_digit(16).ok_or(BitsError::InvalidDigit {
+                index: idx,
+                found: c,
+            })? as u8;
+            bytes[idx / 2] |= if idx % 2 == 0 { nibble << 4 } else { nibble };
+        }
+
+        Ok(BitReader {
+            bytes,
+            len: input.len() * 4,
+            position: 0,
+        })
+    }
+
+    pub fn from_binary_str(input: &str) -> Result<Self, BitsError> {
+        Ok(Self::from_bits(&parse_binary(input)?))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// Moves the cursor to an absolute bit position. Seeking to `len()` is
+    /// allowed and leaves nothing to read.
+    pub fn seek(&mut self, position: usize) -> Result<(), BitsError> {
+        if position > self.len {
+            return Err(BitsError::SeekOutOfBounds {
+                position,
+                len: self.len,
+            });
+        }
+
+        self.position = position;
+        Ok(())
+    }
+
+    fn bit_at(&self, idx: usize) -> bool {
+        self.bytes[idx / 8] & (0x80 >> (idx % 8)) != 0
+    }
+
+    fn peek_bits(&self, count: usize, max: usize) -> Result<u128, BitsError> {
+        if count > max {
+            return Err(BitsError::TooWide {
+                requested: count,
+                max,
+            });
+        }
+
+        if count > self.remaining() {
+            return Err(BitsError::OutOfBounds {
+                position: self.position,
+                requested: count,
+                len: self.len,
+            });
+        }
+
+        Ok((self.position..self.position + count)
+            .fold(0, |out, idx| (out << 1) | self.bit_at(idx) as u128))
+    }
+
+    pub fn peek(&self, count: usize) -> Result<u64, BitsError> {
+        self.peek_bits(count, 64).map(|value| value as u64)
+    }
+
+    pub fn peek_u128(&self, count: usize) -> Result<u128, BitsError> {
+        self.peek_bits(count, 128)
+    }
+
+    pub fn read(&mut self, count: usize) -> Result<u64, BitsError> {
+        let value = self.peek(count)?;
+        self.position += count;
+        Ok(value)
+    }
+
+    pub fn read_u128(&mut self, count: usize) -> Result<u128, BitsError> {
+        let value = self.peek_u128(count)?;
+        self.position += count;
+        Ok(value)
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, BitsError> {
+        self.read(1).map(|bit| bit == 1)
+    }
+}
+
+/// Parses a string of '0' and '1' characters
+pub fn parse_binary(input: &str) -> Result<Vec<bool>, BitsError> {
+    input
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| match c {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            _ => Err(BitsError::InvalidDigit {
+                index: idx,
+                found: c,
+            }),
+        })
+        .collect()
+}
+
+fn fold_bits<I>(bits: I, max: usize) -> Result<u128, BitsError>
+where
+    I: IntoIterator<Item = bool>,
+{
+    let mut count = 0;
+    let mut out: u128 = 0;
+
+    for bit in bits {
+        count += 1;
+        if count > max {
+            return Err(BitsError::TooWide {
+                requested: count,
+                max,
+            });
+        }
+        out = (out << 1) | bit as u128;
+    }
+
+    Ok(out)
+}
+
+/// Interprets the bits as an MSB-first unsigned number
+pub fn to_u64<I>(bits: I) -> Result<u64, BitsError>
+where
+    I: IntoIterator<Item = bool>,
+{
+    fold_bits(bits, 64).map(|value| value as u64)
+}
+
+pub fn to_u128<I>(bits: I) -> Result<u128, BitsError>
+where
+    I: IntoIterator<Item = bool>,
+{
+    fold_bits(bits, 128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_read_peek_seek() {
+        let mut reader = BitReader::from_hex("D2FE28").unwrap();
+        assert_eq!(reader.len(), 24);
+        assert_eq!(reader.peek(3), Ok(6));
+        assert_eq!(reader.read(3), Ok(6));
+        assert_eq!(reader.read(3), Ok(4));
+        assert_eq!(reader.position(), 6);
+        assert_eq!(reader.remaining(), 18);
+        assert_eq!(reader.read_bit(), Ok(true));
+
+        reader.seek(0).unwrap();
+        assert_eq!(reader.read(24), Ok(0xD2FE28));
+        assert_eq!(
+            reader.seek(25),
+            Err(BitsError::SeekOutOfBounds {
+                position: 25,
+                len: 24
+            })
+        );
+        assert_eq!(
+            reader.seek(25).unwrap_err().to_string(),
+            "Can't seek to position 25: only 24 bit(s) available"
+        );
+        assert_eq!(reader.seek(24), Ok(()));
+    }
+
+    #[test]
+    fn reader_bounds() {
+        let mut reader = BitReader::from_bytes(&[0xFF]);
+        assert_eq!(
+            reader.read(9),
+            Err(BitsError::OutOfBounds {
+                position: 0,
+                requested: 9,
+                len: 8
+            })
+        );
+        assert_eq!(
+            reader.peek(65),
+            Err(BitsError::TooWide {
+                requested: 65,
+                max: 64
+            })
+        );
+        assert!(BitReader::from_hex("0G").is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        let bits = parse_binary("10110").unwrap();
+        assert_eq!(to_u64(bits.iter().copied()), Ok(22));
+        assert_eq!(to_u128(vec![true; 100]), Ok((1 << 100) - 1));
+        assert!(to_u64(vec![true; 65]).is_err());
+        assert_eq!(
+            parse_binary("10a"),
+            Err(BitsError::InvalidDigit {
+                index: 2,
+                found: 'a'
+            })
+        );
+        assert_eq!(BitReader::from_binary_str("0101").unwrap().read(4), Ok(5));
+    }
+}
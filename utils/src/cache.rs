@@ -0,0 +1,177 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_CACHE_DIR: &str = ".aoc-cache";
+
+/// Stores solver answers on disk, one file per answer. Entries are keyed by
+/// the solver version, the part label and the input bytes, so changing any of
+/// them leads to a cache miss instead of a stale answer.
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    dir: PathBuf,
+    version: String,
+}
+
+impl ResultCache {
+    pub fn new<P: AsRef<Path>>(dir: P, version: &str) -> Self {
+        ResultCache {
+            dir: dir.as_ref().to_path_buf(),
+            version: version.to_string(),
+        }
+    }
+
+    pub fn key(&self, label: &str, input: &[u8]) -> String {
+        let mut hash = fnv1a(FNV_OFFSET, self.version.as_bytes());
+        // separators keep ("ab", "c") and ("a", "bc") apart
+        hash = fnv1a(hash, &[0]);
+        hash = fnv1a(hash, label.as_bytes());
+        hash = fnv1a(hash, &[0]);
+        hash = fnv1a(hash, input);
+
+        format!("{hash:016x}")
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.txt"))
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.path(key)).ok()
+    }
+
+    pub fn put(&self, key: &str, answer: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(key), answer)
+    }
+}
+
+/// Removes every cached answer in `dir` and returns how many were deleted
+pub fn clear<P: AsRef<Path>>(dir: P) -> io::Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+
+    let mut removed = 0;
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Hashes every `.rs` file under `dirs`, with its path, into a version for
+/// `ResultCache`. Any change to the sources then invalidates cached answers.
+pub fn source_version<P: AsRef<Path>>(dirs: &[P]) -> io::Result<String> {
+    let mut files = Vec::new();
+    let mut pending = dirs
+        .iter()
+        .map(|dir| dir.as_ref().to_path_buf())
+        .collect::<Vec<_>>();
+
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    let mut hash = FNV_OFFSET;
+    for path in files {
+        hash = fnv1a(hash, path.to_string_lossy().as_bytes());
+        hash = fnv1a(hash, &[0]);
+        hash = fnv1a(hash, &fs::read(&path)?);
+        hash = fnv1a(hash, &[0]);
+    }
+
+    Ok(format!("{hash:016x}"))
+}
+
+/// For the build script of a day using `Runner::with_cache`: sets the
+/// `SOLVER_VERSION` environment variable to the `source_version` of the day's
+/// sources and of this crate's, which the day reads with
+/// `env!("SOLVER_VERSION")`.
+pub fn emit_solver_version() {
+    let day = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("Not run by cargo")).join("src");
+    let utils = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let version = source_version(&[&day, &utils]).expect("Failed to read the solver sources");
+
+    println!("cargo:rerun-if-changed={}", day.display());
+    println!("cargo:rerun-if-changed={}", utils.display());
+    println!("cargo:rustc-env=SOLVER_VERSION={version}");
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// std's `DefaultHasher` isn't guaranteed to be stable between Rust releases,
+// which would silently invalidate the cache, so FNV-1a is used instead
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_get_clear() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+        let cache = ResultCache::new(&dir, "day00@0.1.0");
+
+        let key = cache.key("result#1", b"1\n2\n3");
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "42").unwrap();
+        assert_eq!(cache.get(&key), Some("42".to_string()));
+
+        assert_ne!(key, cache.key("result#2", b"1\n2\n3"));
+        assert_ne!(key, cache.key("result#1", b"1\n2\n4"));
+        assert_ne!(
+            key,
+            ResultCache::new(&dir, "day00@0.2.0").key("result#1", b"1\n2\n3")
+        );
+
+        assert_eq!(clear(&dir).unwrap(), 1);
+        assert_eq!(cache.get(&key), None);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn source_version_follows_every_file() {
+        let dir = std::env::temp_dir().join(format!("aoc-source-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("nested/grid.rs"), "pub struct Grid;").unwrap();
+        fs::write(dir.join("data.txt"), "1\n2").unwrap();
+
+        let version = source_version(&[&dir]).unwrap();
+        assert_eq!(source_version(&[&dir]).unwrap(), version);
+
+        // the input is part of the cache key already
+        fs::write(dir.join("data.txt"), "3\n4").unwrap();
+        assert_eq!(source_version(&[&dir]).unwrap(), version);
+
+        fs::write(dir.join("nested/grid.rs"), "pub struct Grid(u8);").unwrap();
+        assert_ne!(source_version(&[&dir]).unwrap(), version);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
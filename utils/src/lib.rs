@@ -1,3 +1,10 @@
+pub mod bits;
+pub mod cache;
+pub mod registry;
+pub mod report;
+pub mod rng;
+pub mod runner;
+
 use std::fmt::Display;
 
 pub fn print_matrix<T>(matrix: &Vec<Vec<T>>)
@@ -0,0 +1,144 @@
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Display},
+};
+
+type Solver<I, O> = Box<dyn Fn(&I) -> O + Send + Sync>;
+
+/// Named implementations of the same part of a puzzle, e.g. a naive and an
+/// optimised one. The first implementation registered is the reference the
+/// others are compared against.
+pub struct Implementations<I: ?Sized, O> {
+    label: &'static str,
+    entries: Vec<(&'static str, Solver<I, O>)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Disagreement<O> {
+    pub label: &'static str,
+    pub results: Vec<(&'static str, O)>,
+}
+
+impl<O: Debug> Display for Disagreement<O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: implementations disagree", self.label)?;
+        for (name, result) in &self.results {
+            writeln!(f, "  {name}: {result:?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: ?Sized, O: PartialEq> Implementations<I, O> {
+    pub fn new(label: &'static str) -> Self {
+        Implementations {
+            label,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn register<F>(mut self, name: &'static str, solver: F) -> Self
+    where
+        F: Fn(&I) -> O + Send + Sync + 'static,
+    {
+        self.entries.push((name, Box::new(solver)));
+        self
+    }
+
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|(name, _)| *name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&(dyn Fn(&I) -> O + Send + Sync)> {
+        self.entries
+            .iter()
+            .find(|(entry_name, _)| *entry_name == name)
+            .map(|(_, solver)| solver.as_ref())
+    }
+
+    /// Solves with the reference implementation. Panics if nothing is
+    /// registered.
+    pub fn solve(&self, input: &I) -> O {
+        (self.entries[0].1)(input)
+    }
+
+    /// Runs every implementation on `input` and returns the shared answer
+    pub fn check(&self, input: &I) -> Result<O, Disagreement<O>> {
+        let mut results: Vec<(&'static str, O)> = self
+            .entries
+            .iter()
+            .map(|(name, solver)| (*name, solver(input)))
+            .collect();
+
+        if results.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+            Ok(results.swap_remove(0).1)
+        } else {
+            Err(Disagreement {
+                label: self.label,
+                results,
+            })
+        }
+    }
+}
+
+/// Checks every part against every input and prints each disagreement.
+/// Returns the number of disagreements found.
+pub fn diff<I, O, B>(parts: &[&Implementations<I, O>], inputs: &[B]) -> usize
+where
+    I: ?Sized + Debug,
+    O: PartialEq + Debug,
+    B: Borrow<I>,
+{
+    let mut disagreements = 0;
+
+    for (idx, input) in inputs.iter().enumerate() {
+        for part in parts {
+            if let Err(disagreement) = part.check(input.borrow()) {
+                disagreements += 1;
+                println!("input #{idx}: {:?}", input.borrow());
+                print!("{disagreement}");
+            }
+        }
+    }
+
+    for part in parts {
+        println!(
+            "{}: checked [{}] on {} input(s)",
+            part.label(),
+            part.names().collect::<Vec<_>>().join(", "),
+            inputs.len()
+        );
+    }
+
+    disagreements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_disagreement() {
+        let sum = Implementations::<[u32], u32>::new("sum")
+            .register("iter", |values| values.iter().sum())
+            .register("loop", |values| {
+                let mut total = 0;
+                for value in values {
+                    total += value;
+                }
+                total
+            });
+        let broken = Implementations::<[u32], u32>::new("broken")
+            .register("iter", |values| values.iter().sum())
+            .register("off_by_one", |values| values.iter().skip(1).sum());
+
+        assert_eq!(sum.check(&[1, 2, 3]), Ok(6));
+        assert_eq!(broken.solve(&[1, 2, 3]), 6);
+        assert!(broken.check(&[1, 2, 3]).is_err());
+        assert_eq!(diff(&[&sum, &broken], &[vec![1, 2, 3], vec![]]), 1);
+    }
+}
@@ -0,0 +1,354 @@
+use std::{fmt::Write, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    Cached,
+    TimedOut,
+    Failed,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Solved => "solved",
+            Status::Cached => "cached",
+            Status::TimedOut => "timed_out",
+            Status::Failed => "failed",
+        }
+    }
+
+    fn parse(input: &str) -> Result<Self, String> {
+        match input {
+            "solved" => Ok(Status::Solved),
+            "cached" => Ok(Status::Cached),
+            "timed_out" => Ok(Status::TimedOut),
+            "failed" => Ok(Status::Failed),
+            _ => Err(format!("Unknown status: {}", input)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub label: String,
+    pub status: Status,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rendering {
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub name: String,
+    pub passed: bool,
+}
+
+/// Everything the workspace report shows about a single day. The runner fills
+/// in answers, input size and renderings; examples are added by the report
+/// tool from the day's tests.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DayReport {
+    pub name: String,
+    pub input_bytes: usize,
+    pub input_lines: usize,
+    pub answers: Vec<Answer>,
+    pub renderings: Vec<Rendering>,
+    pub examples: Vec<Example>,
+}
+
+// Fields are tab separated, so tabs, newlines and backslashes get escaped
+fn escape(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+impl DayReport {
+    pub fn new(name: &str) -> Self {
+        DayReport {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Line based format used to pass the report from a day's binary to the
+    /// report tool
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "day\t{}", escape(&self.name)).unwrap();
+        writeln!(out, "input\t{}\t{}", self.input_bytes, self.input_lines).unwrap();
+
+        for answer in &self.answers {
+            writeln!(
+                out,
+                "answer\t{}\t{}\t{}\t{}",
+                escape(&answer.label),
+                answer.status.as_str(),
+                escape(&answer.value),
+                answer.elapsed.as_nanos()
+            )
+            .unwrap();
+        }
+
+        for rendering in &self.renderings {
+            writeln!(
+                out,
+                "render\t{}\t{}",
+                escape(&rendering.title),
+                escape(&rendering.text)
+            )
+            .unwrap();
+        }
+
+        for example in &self.examples {
+            writeln!(
+                out,
+                "example\t{}\t{}",
+                escape(&example.name),
+                example.passed
+            )
+            .unwrap();
+        }
+
+        out
+    }
+
+    pub fn from_text(input: &str) -> Result<Self, String> {
+        let mut report = DayReport::default();
+
+        for (idx, line) in input.lines().enumerate() {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            let invalid = || format!("Invalid report line {}: {}", idx + 1, line);
+
+            match fields.as_slice() {
+                ["day", name] => report.name = unescape(name),
+                ["input", bytes, lines] => {
+                    report.input_bytes = bytes.parse().map_err(|_| invalid())?;
+                    report.input_lines = lines.parse().map_err(|_| invalid())?;
+                }
+                ["answer", label, status, value, nanos] => report.answers.push(Answer {
+                    label: unescape(label),
+                    status: Status::parse(status)?,
+                    value: unescape(value),
+                    elapsed: Duration::from_nanos(nanos.parse().map_err(|_| invalid())?),
+                }),
+                ["render", title, text] => report.renderings.push(Rendering {
+                    title: unescape(title),
+                    text: unescape(text),
+                }),
+                ["example", name, passed] => report.examples.push(Example {
+                    name: unescape(name),
+                    passed: passed.parse().map_err(|_| invalid())?,
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn answer_summary(answer: &Answer) -> (String, String) {
+    let value = match answer.status {
+        Status::Solved | Status::Cached if answer.value.contains('\n') => "see below".to_string(),
+        Status::Solved | Status::Cached => answer.value.clone(),
+        Status::TimedOut => "timed out".to_string(),
+        Status::Failed => format!("failed: {}", answer.value),
+    };
+
+    let time = match answer.status {
+        Status::Cached => "cached".to_string(),
+        _ => format!("{:.2?}", answer.elapsed),
+    };
+
+    (value, time)
+}
+
+// Multi-line answers (e.g. letters drawn with dots) don't fit into a table
+// cell, so they are shown next to the other renderings
+fn renderings(report: &DayReport) -> Vec<Rendering> {
+    report
+        .answers
+        .iter()
+        .filter(|answer| answer.value.contains('\n'))
+        .map(|answer| Rendering {
+            title: answer.label.clone(),
+            text: answer.value.clone(),
+        })
+        .chain(report.renderings.iter().cloned())
+        .collect()
+}
+
+pub fn to_markdown(title: &str, reports: &[DayReport]) -> String {
+    let mut out = String::new();
+    writeln!(out, "# {title}").unwrap();
+
+    for report in reports {
+        writeln!(out, "\n## {}\n", report.name).unwrap();
+        writeln!(
+            out,
+            "Input: {} bytes, {} lines\n",
+            report.input_bytes, report.input_lines
+        )
+        .unwrap();
+
+        writeln!(out, "| Part | Answer | Time |").unwrap();
+        writeln!(out, "| --- | --- | --- |").unwrap();
+        for answer in &report.answers {
+            let (value, time) = answer_summary(answer);
+            writeln!(out, "| {} | {} | {} |", answer.label, value, time).unwrap();
+        }
+
+        if !report.examples.is_empty() {
+            writeln!(out, "\n| Example | Result |").unwrap();
+            writeln!(out, "| --- | --- |").unwrap();
+            for example in &report.examples {
+                let result = if example.passed { "pass" } else { "FAIL" };
+                writeln!(out, "| {} | {} |", example.name, result).unwrap();
+            }
+        }
+
+        for rendering in renderings(report) {
+            writeln!(out, "\n### {}\n", rendering.title).unwrap();
+            writeln!(out, "```text\n{}\n```", rendering.text.trim_end()).unwrap();
+        }
+    }
+
+    out
+}
+
+fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn to_html(title: &str, reports: &[DayReport]) -> String {
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>\n<html>\n<head>").unwrap();
+    writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(
+        out,
+        "<title>{}</title>\n</head>\n<body>",
+        escape_html(title)
+    )
+    .unwrap();
+    writeln!(out, "<h1>{}</h1>", escape_html(title)).unwrap();
+
+    for report in reports {
+        writeln!(out, "<h2>{}</h2>", escape_html(&report.name)).unwrap();
+        writeln!(
+            out,
+            "<p>Input: {} bytes, {} lines</p>",
+            report.input_bytes, report.input_lines
+        )
+        .unwrap();
+
+        writeln!(
+            out,
+            "<table>\n<tr><th>Part</th><th>Answer</th><th>Time</th></tr>"
+        )
+        .unwrap();
+        for answer in &report.answers {
+            let (value, time) = answer_summary(answer);
+            writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&answer.label),
+                escape_html(&value),
+                escape_html(&time)
+            )
+            .unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+
+        if !report.examples.is_empty() {
+            writeln!(out, "<table>\n<tr><th>Example</th><th>Result</th></tr>").unwrap();
+            for example in &report.examples {
+                let result = if example.passed { "pass" } else { "FAIL" };
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    escape_html(&example.name),
+                    result
+                )
+                .unwrap();
+            }
+            writeln!(out, "</table>").unwrap();
+        }
+
+        for rendering in renderings(report) {
+            writeln!(out, "<h3>{}</h3>", escape_html(&rendering.title)).unwrap();
+            writeln!(out, "<pre>{}</pre>", escape_html(rendering.text.trim_end())).unwrap();
+        }
+    }
+
+    writeln!(out, "</body>\n</html>").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let mut report = DayReport::new("day13");
+        report.input_bytes = 42;
+        report.input_lines = 3;
+        report.answers.push(Answer {
+            label: "result#1".to_string(),
+            status: Status::Solved,
+            value: "17".to_string(),
+            elapsed: Duration::from_micros(120),
+        });
+        report.answers.push(Answer {
+            label: "result#2".to_string(),
+            status: Status::Cached,
+            value: "# #\n\t\\".to_string(),
+            elapsed: Duration::ZERO,
+        });
+        report.examples.push(Example {
+            name: "tests::part1_example".to_string(),
+            passed: true,
+        });
+
+        let parsed = DayReport::from_text(&report.to_text()).unwrap();
+        assert_eq!(parsed, report);
+
+        let markdown = to_markdown("Report", &[parsed]);
+        assert!(markdown.contains("| result#1 | 17 |"));
+        assert!(markdown.contains("### result#2"));
+        assert!(DayReport::from_text("answer\tbroken").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+/// Small seeded pseudo-random generator (SplitMix64). Good enough for
+/// generating puzzle inputs and simulations, not for anything secure.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `range`. Panics on an empty range.
+    pub fn gen_range(&mut self, range: Range<u64>) -> u64 {
+        assert!(range.start < range.end, "Empty range");
+        let span = range.end - range.start;
+        // rejection sampling to avoid modulo bias
+        let zone = u64::MAX - u64::MAX % span;
+
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return range.start + value % span;
+            }
+        }
+    }
+
+    pub fn gen_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.gen_range(0..i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_and_in_range() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        for _ in 0..1000 {
+            let value = a.gen_range(3..9);
+            assert!((3..9).contains(&value));
+            assert!((0.0..1.0).contains(&a.gen_f64()));
+        }
+
+        let mut items = (0..50).collect::<Vec<_>>();
+        a.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+}
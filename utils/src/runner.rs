@@ -0,0 +1,500 @@
+use std::{
+    cell::{Cell, RefCell},
+    env, error,
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    cache::{self, ResultCache, DEFAULT_CACHE_DIR},
+    report::{Answer, DayReport, Rendering, Status},
+};
+
+/// Flag shared between the runner and a solver. Solvers that may run for a
+/// long time should call `check` inside their main loop.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Solver was cancelled")
+    }
+}
+
+impl error::Error for Cancelled {}
+
+#[derive(Debug)]
+pub enum Outcome<T> {
+    Solved { answer: T, elapsed: Duration },
+    Cached(T),
+    TimedOut(Duration),
+    Failed(String),
+}
+
+impl<T> Outcome<T> {
+    pub fn answer(self) -> Option<T> {
+        match self {
+            Outcome::Solved { answer, .. } | Outcome::Cached(answer) => Some(answer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Solve every part and print the answers
+    Solve,
+    /// Compare all registered implementations on the puzzle input and on
+    /// `samples` generated inputs
+    Diff { samples: usize, seed: u64 },
+    /// Solve every part and write a report for the workspace report tool,
+    /// to stdout when no output file is given
+    Report { output: Option<PathBuf> },
+    /// Command specific to a day, with every argument that follows it
+    Day { name: String, args: Vec<String> },
+}
+
+pub struct Runner {
+    command: Command,
+    budget: Option<Duration>,
+    cache_dir: Option<PathBuf>,
+    cache: Option<ResultCache>,
+    failed: Cell<bool>,
+    report: RefCell<DayReport>,
+}
+
+impl Runner {
+    pub fn new(budget: Option<Duration>) -> Self {
+        Runner {
+            command: Command::Solve,
+            budget,
+            cache_dir: Some(PathBuf::from(DEFAULT_CACHE_DIR)),
+            cache: None,
+            failed: Cell::new(false),
+            report: RefCell::new(DayReport::new(&day_name())),
+        }
+    }
+
+    /// Uses `default_budget` unless overridden on the command line with
+    /// `--timeout <seconds>` or disabled with `--no-timeout`.
+    ///
+    /// Caching is controlled with `--no-cache` and `--cache-dir <dir>`, and
+    /// the `clear-cache` command empties the cache directory and exits.
+    ///
+    /// The `diff` command takes `--samples <count>` and `--seed <seed>`, the
+    /// `report` command takes `--output <file>`. Any other command is left to
+    /// the day together with the arguments after it.
+    pub fn from_args(default_budget: Duration) -> Result<Self, String> {
+        Self::parse_args(default_budget, env::args().skip(1))
+    }
+
+    fn parse_args(
+        default_budget: Duration,
+        mut args: impl Iterator<Item = String>,
+    ) -> Result<Self, String> {
+        let mut runner = Self::new(Some(default_budget));
+        let mut clear_cache = false;
+        let mut diff = false;
+        let mut report = false;
+        let mut output = None;
+        let mut samples = 100;
+        let mut seed = 0;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--timeout" => {
+                    let value = args.next().ok_or("Missing value for --timeout")?;
+                    let budget = value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                        .ok_or_else(|| format!("Invalid timeout: {}", value))?;
+                    runner.budget = Some(budget);
+                }
+                "--no-timeout" => runner.budget = None,
+                "--no-cache" => runner.cache_dir = None,
+                "--cache-dir" => {
+                    let value = args.next().ok_or("Missing value for --cache-dir")?;
+                    runner.cache_dir = Some(PathBuf::from(value));
+                }
+                "clear-cache" => clear_cache = true,
+                "diff" => diff = true,
+                "report" => report = true,
+                "--output" => {
+                    let value = args.next().ok_or("Missing value for --output")?;
+                    output = Some(PathBuf::from(value));
+                }
+                "--samples" => {
+                    let value = args.next().ok_or("Missing value for --samples")?;
+                    samples = value
+                        .parse()
+                        .map_err(|_| format!("Invalid sample count: {}", value))?;
+                }
+                "--seed" => {
+                    let value = args.next().ok_or("Missing value for --seed")?;
+                    seed = value
+                        .parse()
+                        .map_err(|_| format!("Invalid seed: {}", value))?;
+                }
+                name if !name.starts_with('-') => {
+                    runner.command = Command::Day {
+                        name: name.to_string(),
+                        args: args.by_ref().collect(),
+                    };
+                }
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+
+        if clear_cache {
+            let dir = runner
+                .cache_dir
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIR));
+            let removed = cache::clear(&dir)
+                .map_err(|err| format!("Failed to clear {}: {}", dir.display(), err))?;
+            println!(
+                "Removed {} cached answer(s) from {}",
+                removed,
+                dir.display()
+            );
+            process::exit(0);
+        }
+
+        if let Command::Day { .. } = runner.command {
+            if diff || report {
+                return Err("Only one command can be given".to_string());
+            }
+        } else if diff {
+            runner.command = Command::Diff { samples, seed };
+        } else if report {
+            runner.command = Command::Report { output };
+        }
+
+        Ok(runner)
+    }
+
+    /// Enables the result cache for `run_cached` unless it was disabled on
+    /// the command line. `version` should change whenever the solvers do,
+    /// like the one `cache::emit_solver_version` sets up.
+    pub fn with_cache(mut self, version: &str) -> Self {
+        self.cache = self
+            .cache_dir
+            .as_ref()
+            .map(|dir| ResultCache::new(dir, version));
+        self
+    }
+
+    /// Records the size of the puzzle input for the report
+    pub fn with_input(self, input: &str) -> Self {
+        {
+            let mut report = self.report.borrow_mut();
+            report.input_bytes = input.len();
+            report.input_lines = input.lines().count();
+        }
+        self
+    }
+
+    /// Adds a rendering (a drawing of the solution) to the report. `render`
+    /// is only called when a report was requested.
+    pub fn render<F>(&self, title: &str, render: F)
+    where
+        F: FnOnce() -> String,
+    {
+        if let Command::Report { .. } = self.command {
+            self.report.borrow_mut().renderings.push(Rendering {
+                title: title.to_string(),
+                text: render(),
+            });
+        }
+    }
+
+    /// Runs the solver on a separate thread and prints its answer. When the
+    /// budget runs out the solver's token is cancelled and the part is
+    /// reported as timed out without waiting for the thread to finish.
+    pub fn run<T, F>(&self, label: &str, solver: F) -> Outcome<T>
+    where
+        T: Display + Send + 'static,
+        F: FnOnce(&CancelToken) -> Result<T, Cancelled> + Send + 'static,
+    {
+        self.run_fallible(label, solver)
+    }
+
+    /// Same as `run` for solvers that can fail, e.g. on invalid input. The
+    /// error is reported as the reason the part failed.
+    pub fn run_fallible<T, E, F>(&self, label: &str, solver: F) -> Outcome<T>
+    where
+        T: Display + Send + 'static,
+        E: Display + Send + 'static,
+        F: FnOnce(&CancelToken) -> Result<T, E> + Send + 'static,
+    {
+        let token = CancelToken::new();
+        let solver_token = token.clone();
+        let (sender, receiver) = mpsc::channel();
+        let start = Instant::now();
+
+        thread::spawn(move || {
+            // the receiver is gone if the runner already gave up on us
+            let _ = sender.send(solver(&solver_token));
+        });
+
+        let received = match self.budget {
+            Some(budget) => receiver.recv_timeout(budget),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        let outcome = match received {
+            Ok(Ok(answer)) => Outcome::Solved {
+                answer,
+                elapsed: start.elapsed(),
+            },
+            // a solver only gives up on its own once its token is cancelled
+            Ok(Err(_)) if token.is_cancelled() => Outcome::TimedOut(start.elapsed()),
+            Ok(Err(err)) => Outcome::Failed(err.to_string()),
+            Err(RecvTimeoutError::Timeout) => {
+                token.cancel();
+                Outcome::TimedOut(start.elapsed())
+            }
+            Err(RecvTimeoutError::Disconnected) => Outcome::Failed("Solver panicked".to_string()),
+        };
+
+        self.record(label, &outcome);
+        outcome
+    }
+
+    fn record<T: Display>(&self, label: &str, outcome: &Outcome<T>) {
+        let (status, value, elapsed) = match outcome {
+            Outcome::Solved { answer, elapsed } => (Status::Solved, answer.to_string(), *elapsed),
+            Outcome::Cached(answer) => (Status::Cached, answer.to_string(), Duration::ZERO),
+            Outcome::TimedOut(elapsed) => (Status::TimedOut, String::new(), *elapsed),
+            Outcome::Failed(err) => (Status::Failed, err.clone(), Duration::ZERO),
+        };
+
+        let detail = match status {
+            Status::Solved => format!("{elapsed:.2?}"),
+            Status::Cached => "cached".to_string(),
+            Status::TimedOut => format!("timed out after {elapsed:.2?}"),
+            Status::Failed => format!("failed: {value}"),
+        };
+
+        match status {
+            Status::Solved | Status::Cached if value.contains('\n') => {
+                println!("{label} ({detail}):\n{value}")
+            }
+            Status::Solved | Status::Cached => println!("{label}: {value} ({detail})"),
+            Status::TimedOut | Status::Failed => {
+                self.failed.set(true);
+                println!("{label}: {detail}");
+            }
+        }
+
+        self.report.borrow_mut().answers.push(Answer {
+            label: label.to_string(),
+            status,
+            value,
+            elapsed,
+        });
+    }
+
+    /// Same as `run`, but serves the answer from the cache when one exists
+    /// for this `input` and stores freshly computed answers
+    pub fn run_cached<T, F>(&self, label: &str, input: &[u8], solver: F) -> Outcome<T>
+    where
+        T: Display + FromStr + Send + 'static,
+        F: FnOnce(&CancelToken) -> Result<T, Cancelled> + Send + 'static,
+    {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.run(label, solver),
+        };
+
+        let key = cache.key(label, input);
+
+        if let Some(answer) = cache.get(&key).and_then(|answer| answer.parse::<T>().ok()) {
+            let outcome = Outcome::Cached(answer);
+            self.record(label, &outcome);
+            return outcome;
+        }
+
+        let outcome = self.run(label, solver);
+
+        if let Outcome::Solved { answer, .. } = &outcome {
+            if let Err(err) = cache.put(&key, &answer.to_string()) {
+                eprintln!("Failed to cache {label}: {err}");
+            }
+        }
+
+        outcome
+    }
+
+    pub fn command(&self) -> Command {
+        self.command.clone()
+    }
+
+    /// Prints what a `Command::Day` returned, errors going to stderr and
+    /// failing the run
+    pub fn print_day_output<E: Display>(&self, output: Result<String, E>) {
+        match output {
+            Ok(output) => print!("{output}"),
+            Err(err) => {
+                eprintln!("{err}");
+                self.mark_failed();
+            }
+        }
+    }
+
+    /// Reads the file given to a day command's `--input` option
+    pub fn read_input(path: &str) -> Result<String, String> {
+        fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path, err))
+    }
+
+    /// Makes the process exit with a failure code, e.g. after a failed check
+    pub fn mark_failed(&self) {
+        self.failed.set(true);
+    }
+
+    /// Writes the report if one was requested and returns the exit code
+    pub fn finish(self) -> ExitCode {
+        if let Command::Report { output } = &self.command {
+            let text = self.report.borrow().to_text();
+            let written = match output {
+                Some(path) => fs::write(path, text),
+                None => {
+                    print!("{text}");
+                    Ok(())
+                }
+            };
+
+            if let Err(err) = written {
+                eprintln!("Failed to write report: {err}");
+                self.failed.set(true);
+            }
+        }
+
+        self.exit_code()
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        if self.failed.get() {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn day_name() -> String {
+    env::args()
+        .next()
+        .as_ref()
+        .and_then(|path| Path::new(path).file_stem())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solved_within_budget() {
+        let runner = Runner::new(Some(Duration::from_secs(5)));
+        let outcome = runner.run("answer", |_| Ok(42));
+        assert_eq!(outcome.answer(), Some(42));
+        assert_eq!(runner.exit_code(), ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn endless_solver_times_out() {
+        let runner = Runner::new(Some(Duration::from_millis(50)));
+        let outcome = runner.run("endless", |token| loop {
+            token.check()?;
+            thread::sleep(Duration::from_millis(1));
+        });
+
+        assert!(matches!(outcome, Outcome::<u32>::TimedOut(_)));
+        assert_eq!(runner.exit_code(), ExitCode::FAILURE);
+    }
+
+    #[test]
+    fn solver_error_is_reported() {
+        let runner = Runner::new(Some(Duration::from_secs(5)));
+        let outcome = runner.run_fallible("strict", |_| {
+            Err::<u32, _>("line 3: invalid depth \"x\"".to_string())
+        });
+
+        assert!(matches!(&outcome, Outcome::Failed(err) if err == "line 3: invalid depth \"x\""));
+        assert_eq!(
+            runner.report.borrow().answers[0].value,
+            "line 3: invalid depth \"x\""
+        );
+        assert_eq!(runner.exit_code(), ExitCode::FAILURE);
+
+        let runner = Runner::new(Some(Duration::from_secs(5)));
+        let outcome = runner.run_fallible("lenient", |_| Ok::<_, String>(7));
+        assert_eq!(outcome.answer(), Some(7));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(|arg| arg.to_string());
+            Runner::parse_args(Duration::from_secs(10), args).map(|runner| runner.budget)
+        };
+
+        assert_eq!(
+            parse(&["--timeout", "0.5"]),
+            Ok(Some(Duration::from_millis(500)))
+        );
+        for timeout in ["-1", "nan", "inf", "abc"] {
+            assert_eq!(
+                parse(&["--timeout", timeout]).err(),
+                Some(format!("Invalid timeout: {}", timeout))
+            );
+        }
+        assert_eq!(
+            parse(&["--timeout"]).err(),
+            Some("Missing value for --timeout".to_string())
+        );
+        assert_eq!(
+            parse(&["--verbose"]).err(),
+            Some("Unknown argument: --verbose".to_string())
+        );
+    }
+}